@@ -0,0 +1,62 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Build script which extracts the module-level documentation of each rule, so that it can be
+//! shown to users at runtime without embedding the rules' full sources in the binary.
+//!
+//! For each file `src/rules/ruleXXX.rs`, a constant named `RULEXXX` is written to
+//! `$OUT_DIR/rule_docs.rs`, containing the text of the file's `//!` comment without the comment
+//! markers. See `src/rules/docs.rs`.
+
+use std::{env, fmt::Write, fs, path::Path};
+
+/// Directory containing the rule modules.
+const RULES_DIR: &str = "src/rules";
+
+fn main() {
+    println!("cargo::rerun-if-changed={RULES_DIR}");
+
+    let mut entries: Vec<_> = fs::read_dir(RULES_DIR)
+        .expect("Failed to read rules directory")
+        .map(|entry| entry.expect("Failed to read rules directory").path())
+        .collect();
+    entries.sort();
+
+    let mut output = String::new();
+    for path in entries {
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if !name.starts_with("rule") || path.extension().is_none_or(|ext| ext != "rs") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).expect("Failed to read rule module");
+        let doc: String = source
+            .lines()
+            .skip_while(|line| !line.starts_with("//!"))
+            .map_while(|line| line.strip_prefix("//!"))
+            .map(|line| line.strip_prefix(' ').unwrap_or(line))
+            .fold(String::new(), |mut doc, line| {
+                doc.push_str(line);
+                doc.push('\n');
+                doc
+            });
+        writeln!(output, "pub const {}: &str = {doc:?};", name.to_ascii_uppercase())
+            .expect("Failed to write to string");
+    }
+
+    let out_dir = env::var_os("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("rule_docs.rs"), output)
+        .expect("Failed to write rule documentation");
+}
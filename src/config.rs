@@ -0,0 +1,375 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Project configuration file.
+//!
+//! # Discovery
+//!
+//! The configuration for a source file is read from the first file named `westwood.toml` found
+//! in the directory containing the source file or any of its ancestors. If no such file exists,
+//! the default configuration is used.
+//!
+//! # Format
+//!
+//! All keys are optional. Unknown keys are reported as errors to catch typos.
+//!
+//! ```toml
+//! # Width of a tab character, in columns
+//! tab-width = 8
+//!
+//! # Whether to warn about suppression comments which don't suppress anything (W:A)
+//! report-unused-suppressions = true
+//!
+//! # Each rule is configured in a table named after its code, except W:A, which is only
+//! # controlled by report-unused-suppressions above
+//! [rules."II:A"]
+//! # Whether the rule is checked at all
+//! enabled = true
+//! # Severity of the rule's diagnostics: "error", "warning", "note", or "help"
+//! severity = "error"
+//! # Parameters specific to the rule
+//! max-line-length = 80
+//! indent-width = 2
+//! wrapped-indent-width = 2
+//!
+//! [rules."II:B"]
+//! page-size = 61
+//! max-pages = 2
+//!
+//! # For XI:A and XI:B, a maximum of 0 means there is no limit
+//! [rules."XI:A"]
+//! max-diagnostics = 3
+//!
+//! [rules."XI:B"]
+//! max-diagnostics = 3
+//!
+//! # Checks of the contents of function header comments, which are all off by default
+//! [rules."W:B"]
+//! require-name = true
+//! require-params = true
+//! require-return = true
+//!
+//! # Regular expressions which must each match part of the file header comment
+//! [rules."W:C"]
+//! required-fields = ["Name:", "(?i)author:"]
+//!
+//! # Numbers which may appear outside of `#define`s
+//! [rules."W:D"]
+//! allowed = [0, 1, -1, 2]
+//! allow-case-labels = false
+//!
+//! # Functions whose return values must be checked
+//! [rules."W:E"]
+//! functions = ["fopen", "malloc", "fscanf", "fread"]
+//!
+//! # Whether to check for leaked memory in addition to files
+//! [rules."W:F"]
+//! check-memory = true
+//! ```
+
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use codespan_reporting::diagnostic::Severity;
+use serde::{de::DeserializeOwned, de::Error as _, Deserialize, Deserializer};
+
+use crate::rules::{
+    rule02a::Rule02aConfig, rule02b::Rule02bConfig, rule11a::Rule11aConfig, rule11b::Rule11bConfig,
+    rulewb::RuleWbConfig, rulewc::RuleWcConfig, rulewd::RuleWdConfig, rulewe::RuleWeConfig,
+    rulewf::RuleWfConfig,
+};
+
+/// Name of the configuration file.
+pub const CONFIG_FILE_NAME: &str = "westwood.toml";
+
+/// Top-level configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Width of a tab character, in columns.
+    pub tab_width: usize,
+    /// Whether to warn about suppression comments which don't suppress anything. See
+    /// [`crate::suppress`].
+    pub report_unused_suppressions: bool,
+    /// Configuration of each rule.
+    pub rules: RulesConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tab_width: 8,
+            report_unused_suppressions: true,
+            rules: RulesConfig::default(),
+        }
+    }
+}
+
+/// Configuration of each [rule][crate::rules::api::Rule], keyed by rule code.
+///
+/// W:A has no table because it isn't produced by a rule. Its warnings are reported by
+/// [`crate::suppress`] and are controlled by [`Config::report_unused_suppressions`] instead.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+#[allow(missing_docs)]
+pub struct RulesConfig {
+    #[serde(rename = "I:A")]
+    pub rule01a: RuleConfig,
+    #[serde(rename = "I:B")]
+    pub rule01b: RuleConfig,
+    #[serde(rename = "I:C")]
+    pub rule01c: RuleConfig,
+    #[serde(rename = "I:D")]
+    pub rule01d: RuleConfig,
+    #[serde(rename = "II:A")]
+    pub rule02a: RuleConfig<Rule02aConfig>,
+    #[serde(rename = "II:B")]
+    pub rule02b: RuleConfig<Rule02bConfig>,
+    #[serde(rename = "III:A")]
+    pub rule03a: RuleConfig,
+    #[serde(rename = "III:B")]
+    pub rule03b: RuleConfig,
+    #[serde(rename = "III:C")]
+    pub rule03c: RuleConfig,
+    #[serde(rename = "III:D")]
+    pub rule03d: RuleConfig,
+    #[serde(rename = "III:E")]
+    pub rule03e: RuleConfig,
+    #[serde(rename = "III:F")]
+    pub rule03f: RuleConfig,
+    #[serde(rename = "XI:A")]
+    pub rule11a: RuleConfig<Rule11aConfig>,
+    #[serde(rename = "XI:B")]
+    pub rule11b: RuleConfig<Rule11bConfig>,
+    #[serde(rename = "XI:E")]
+    pub rule11e: RuleConfig,
+    #[serde(rename = "XII:A")]
+    pub rule12a: RuleConfig,
+    #[serde(rename = "W:B")]
+    pub rulewb: RuleConfig<RuleWbConfig>,
+    #[serde(rename = "W:C")]
+    pub rulewc: RuleConfig<RuleWcConfig>,
+    #[serde(rename = "W:D")]
+    pub rulewd: RuleConfig<RuleWdConfig>,
+    #[serde(rename = "W:E")]
+    pub rulewe: RuleConfig<RuleWeConfig>,
+    #[serde(rename = "W:F")]
+    pub rulewf: RuleConfig<RuleWfConfig>,
+}
+
+/// Configuration of a single rule. `P` holds the rule's specific parameters, which are given in
+/// the same table as the common options.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleConfig<P = NoParams> {
+    /// Whether the rule is checked.
+    pub enabled: bool,
+    /// Severity to give the rule's diagnostics, overriding the rule's own.
+    pub severity: Option<SeverityConfig>,
+    /// Rule-specific parameters.
+    pub params: P,
+}
+
+/// We can't use `#[serde(flatten)]` for the parameters because it doesn't support
+/// `deny_unknown_fields`, so we split the common options out of the table manually and deserialize
+/// the rest as the parameters.
+impl<'de, P: DeserializeOwned> Deserialize<'de> for RuleConfig<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut table = toml::Table::deserialize(deserializer)?;
+        let enabled = table
+            .remove("enabled")
+            .map(toml::Value::try_into)
+            .transpose()
+            .map_err(D::Error::custom)?
+            .unwrap_or(true);
+        let severity = table
+            .remove("severity")
+            .map(toml::Value::try_into)
+            .transpose()
+            .map_err(D::Error::custom)?;
+        let params = toml::Value::Table(table).try_into().map_err(D::Error::custom)?;
+        Ok(Self {
+            enabled,
+            severity,
+            params,
+        })
+    }
+}
+
+impl<P: Default> Default for RuleConfig<P> {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: None,
+            params: P::default(),
+        }
+    }
+}
+
+/// Parameters for rules which don't have any.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NoParams {}
+
+/// Diagnostic severity, as written in the configuration file.
+#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+#[allow(missing_docs)]
+pub enum SeverityConfig {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl From<SeverityConfig> for Severity {
+    fn from(value: SeverityConfig) -> Self {
+        match value {
+            SeverityConfig::Error => Severity::Error,
+            SeverityConfig::Warning => Severity::Warning,
+            SeverityConfig::Note => Severity::Note,
+            SeverityConfig::Help => Severity::Help,
+        }
+    }
+}
+
+/// Error produced when loading a configuration file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(PathBuf, io::Error),
+    /// The file is not valid TOML or does not match the expected structure.
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "Cannot read {}: {err}", path.display()),
+            Self::Parse(path, err) => {
+                write!(f, "Invalid configuration in {}: {err}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Parses a configuration from the contents of a configuration file. `path` is only used for
+    /// error messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the contents are invalid or contain unknown keys.
+    pub fn parse(contents: &str, path: &Path) -> Result<Self, ConfigError> {
+        toml::from_str(contents).map_err(|err| ConfigError::Parse(path.to_owned(), err))
+    }
+
+    /// Reads a configuration from the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is invalid.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_owned(), err))?;
+        Self::parse(&contents, path)
+    }
+}
+
+/// Finds the configuration file which applies to files in the directory `dir`, by searching `dir`
+/// and each of its ancestors for a file named [`CONFIG_FILE_NAME`].
+///
+/// Returns [`None`] if no configuration file is found.
+#[must_use]
+pub fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    // The parent of a bare file name is the empty path
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .map(|ancestor| ancestor.join(CONFIG_FILE_NAME))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use codespan_reporting::diagnostic::Severity;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::{Config, ConfigError, SeverityConfig, CONFIG_FILE_NAME};
+
+    #[test]
+    fn parse() {
+        let contents = indoc! { /* toml */ r#"
+            tab-width = 4
+            report-unused-suppressions = false
+
+            [rules."I:A"]
+            enabled = false
+
+            [rules."II:A"]
+            severity = "error"
+            max-line-length = 100
+
+            [rules."XI:A"]
+            max-diagnostics = 0
+        "# };
+        let config = Config::parse(contents, Path::new(CONFIG_FILE_NAME)).unwrap();
+
+        let mut expected = Config {
+            tab_width: 4,
+            report_unused_suppressions: false,
+            ..Default::default()
+        };
+        expected.rules.rule01a.enabled = false;
+        expected.rules.rule02a.severity = Some(SeverityConfig::Error);
+        expected.rules.rule02a.params.max_line_length = 100;
+        expected.rules.rule11a.params.max_diagnostics = 0;
+        assert_eq!(expected, config);
+        assert_eq!(Severity::Error, Severity::from(SeverityConfig::Error));
+    }
+
+    #[test]
+    fn unknown_key() {
+        for contents in [
+            "tab-widht = 4\n",
+            "[rules.\"II:A\"]\nmax-line-lenght = 100\n",
+            "[rules.\"I:A\"]\nmax-line-length = 100\n",
+            "[rules.\"IX:Z\"]\nenabled = false\n",
+        ] {
+            let result = Config::parse(contents, Path::new(CONFIG_FILE_NAME));
+            assert!(matches!(result, Err(ConfigError::Parse(..))), "{contents}");
+        }
+    }
+
+    #[test]
+    fn find_config_file() {
+        let dir = std::env::temp_dir().join(format!("westwood-config-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("a/b")).unwrap();
+        fs::write(dir.join(CONFIG_FILE_NAME), "").unwrap();
+        let found = super::find_config_file(&dir.join("a/b"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(Some(dir.join(CONFIG_FILE_NAME)), found);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Automatic fixing of problems found by [rules][Rule].
+//!
+//! Fixing works in passes. In each pass, the code is parsed and checked, and the fixes attached to
+//! the resulting [lints][Lint] are applied, skipping any fix which overlaps a fix that was already
+//! chosen. Since skipped fixes (and fixes which depend on others having been applied) may apply
+//! cleanly once the code is re-parsed, passes are repeated until no more fixes are produced.
+
+use crate::rules::{
+    api::{Edit, Lint, Rule, SourceInfo},
+    check_source,
+};
+
+/// Maximum number of fixing passes to perform on a single file. This prevents infinite loops in
+/// case two rules' fixes undo each other.
+const MAX_PASSES: usize = 10;
+
+/// Repeatedly checks `code` using the given rules and applies all non-overlapping fixes until no
+/// more fixes can be applied. Returns the fixed code.
+///
+/// Code containing syntax errors is never modified, since the rules' results may not be reliable.
+#[must_use]
+pub fn fix_code(code: &str, rules: &[Box<dyn Rule>]) -> String {
+    let mut code = code.to_owned();
+    for _ in 0..MAX_PASSES {
+        let source = SourceInfo::new(&code);
+        if source.tree.root_node().has_error() {
+            break;
+        }
+        let lints: Vec<Lint> = check_source(&source, rules, false);
+        let edits = select_non_overlapping(lints.iter().map(|lint| lint.fix.as_slice()));
+        if edits.is_empty() {
+            break;
+        }
+        code = apply_edits(&code, edits);
+    }
+    code
+}
+
+/// Selects a set of fixes whose edits do not overlap each other. Each fix is a group of edits
+/// which must either be applied together or not at all. Fixes are considered in order, and a fix
+/// is selected if none of its edits overlap an edit of a previously-selected fix.
+///
+/// Returns the edits of all selected fixes.
+#[must_use]
+pub fn select_non_overlapping<'a>(fixes: impl IntoIterator<Item = &'a [Edit]>) -> Vec<&'a Edit> {
+    let mut selected: Vec<&Edit> = Vec::new();
+    for fix in fixes {
+        let conflicts =
+            fix.iter().any(|edit| selected.iter().any(|other| edits_overlap(edit, other)));
+        if !conflicts {
+            selected.extend(fix);
+        }
+    }
+    selected
+}
+
+/// Returns `true` if the two edits overlap. Edits which touch but do not overlap, e.g. `1..2` and
+/// `2..3`, are not considered overlapping, except if both start at the same position, since the
+/// order in which they are applied would then be ambiguous.
+fn edits_overlap(a: &Edit, b: &Edit) -> bool {
+    (a.range.start < b.range.end && b.range.start < a.range.end) || a.range.start == b.range.start
+}
+
+/// Applies the given edits to `code` and returns the result.
+///
+/// # Panics
+///
+/// Panics if any of the edits overlap or lie outside of `code`.
+#[must_use]
+pub fn apply_edits<'a>(code: &str, edits: impl IntoIterator<Item = &'a Edit>) -> String {
+    let mut edits: Vec<&Edit> = edits.into_iter().collect();
+    edits.sort_by_key(|edit| edit.range.start);
+    let mut result = String::with_capacity(code.len());
+    let mut pos = 0;
+    for edit in edits {
+        assert!(edit.range.start >= pos, "Edits must not overlap");
+        result.push_str(&code[pos..edit.range.start]);
+        result.push_str(&edit.replacement);
+        pos = edit.range.end;
+    }
+    result.push_str(&code[pos..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        config::Config,
+        rules::{api::Edit, get_rules},
+    };
+
+    #[test]
+    fn apply_edits() {
+        let code = "abcdef";
+        let edits = [
+            Edit::replace(4..5, "E"),
+            Edit::delete(0..1),
+            Edit::replace(2..2, "_"),
+        ];
+        assert_eq!("b_cdEf", super::apply_edits(code, &edits));
+    }
+
+    /// Tests that a fix is skipped entirely if any one of its edits overlaps an earlier fix.
+    #[test]
+    fn select_non_overlapping() {
+        let fixes = [
+            vec![Edit::delete(0..2)],
+            vec![Edit::delete(1..3), Edit::delete(5..6)],
+            vec![Edit::delete(2..3)],
+            vec![Edit::replace(2..2, "x")],
+        ];
+        let selected = super::select_non_overlapping(fixes.iter().map(Vec::as_slice));
+        assert_eq!(vec![&Edit::delete(0..2), &Edit::delete(2..3)], selected);
+    }
+
+    /// Tests fixing code with problems found by several rules at once.
+    #[test]
+    fn fix_code() {
+        let code = indoc! { /* c */ "
+            int main() {
+            \tprintf (\"%d %d\\n\",1,2);\t
+              return 0;\r
+            }
+        " };
+        let expected = indoc! { /* c */ "
+            int main() {
+              printf(\"%d %d\\n\", 1, 2);
+              return 0;
+            }
+        " };
+        assert_eq!(expected, super::fix_code(code, &get_rules(&Config::default())));
+    }
+
+    /// Tests that code with syntax errors is not modified.
+    #[test]
+    fn syntax_error() {
+        let code = "int main() {\n\treturn 0 \n";
+        assert_eq!(code, super::fix_code(code, &get_rules(&Config::default())));
+    }
+}
@@ -215,31 +215,67 @@ impl<'src> QueryHelper<'src> {
     }
 }
 
-/// Returns the name of a function defined by a `function_definition` node.
+/// Returns the declarator nodes of a node with a `declarator` field, such as a
+/// `function_definition` or `parameter_declaration`, from the outermost one to the identifier
+/// being declared. Returns [`None`] if there is no such identifier, e.g. for an abstract
+/// declarator.
+#[must_use]
+pub fn declarator_path(node: Node) -> Option<Vec<Node>> {
+    let mut path = Vec::new();
+    let mut node = node.child_by_field_name("declarator")?;
+    loop {
+        path.push(node);
+        node = match node.kind() {
+            "identifier" => return Some(path),
+            "parenthesized_declarator" => node.named_child(0)?,
+            _ => node.child_by_field_name("declarator")?,
+        };
+    }
+}
+
+/// Returns the name of a function defined by a `function_definition` node, or [`None`] if it
+/// can't be determined, e.g. because the definition is generated by a macro.
 ///
 /// # Panics
 ///
-/// This function panics if:
-/// - the given `node`'s [kind][Node::kind()] is not `function_definition`;
-/// - the given `node` does not have an `identifier` child reachable by repeatedly traversing to
-///   the node named by the `declarator` field;
-/// - the node's text is not valid UTF-8
-///
+/// This function panics if the given `node`'s [kind][Node::kind()] is not `function_definition`.
 #[must_use]
-pub fn function_definition_name<'code>(node: Node, code: &'code str) -> &'code str {
+pub fn function_definition_name<'code>(node: Node, code: &'code str) -> Option<&'code str> {
     assert_eq!(
         "function_definition",
         node.kind(),
         "Expected node to have kind `function_definition'"
     );
 
-    let mut node = node;
-    while node.kind() != "identifier" {
-        node = node
-            .child_by_field_name("declarator")
-            .expect("Expected node to have a `declarator' field");
+    // Without a function declarator, tree-sitter has parsed something like `DECL(name) { ... }`,
+    // and the identifier it finds is the macro's argument rather than the function's name.
+    let path = declarator_path(node)?;
+    let name = path.last()?;
+    path.iter()
+        .any(|declarator| declarator.kind() == "function_declarator")
+        .then(|| &code[name.byte_range()])
+}
+
+/// Returns the largest offset no greater than `offset` which is on a character boundary in `text`.
+/// Offsets past the end of `text` are clamped to its length.
+#[must_use]
+pub fn floor_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
     }
-    &code[node.byte_range()]
+    offset
+}
+
+/// Returns the smallest offset no less than `offset` which is on a character boundary in `text`.
+/// Offsets past the end of `text` are clamped to its length.
+#[must_use]
+pub fn ceil_char_boundary(text: &str, offset: usize) -> usize {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset += 1;
+    }
+    offset
 }
 
 /// Gets the number of columns by which this line is indented. Tab characters (U+0009 or `'\t'`)
@@ -257,6 +293,44 @@ pub fn indent_width(line: &str) -> usize {
         .sum()
 }
 
+/// Returns the width of `text` in columns. Tab characters (U+0009 or `'\t'`) advance to the next
+/// tab stop, with tab stops every `tab_width` columns. All other characters are sized using
+/// [`unicode_width`].
+#[must_use]
+pub fn text_width(text: &str, tab_width: usize) -> usize {
+    text.chars().fold(0, |column, c| match c {
+        '\t' => next_tab_stop(column, tab_width),
+        c => column + c.width().unwrap_or(0),
+    })
+}
+
+/// Replaces each tab in `text` with spaces up to the next tab stop, measuring columns the same way
+/// as [`text_width()`].
+#[must_use]
+pub fn expand_tabs(text: &str, tab_width: usize) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut column = 0;
+    for c in text.chars() {
+        if c == '\t' {
+            let next = next_tab_stop(column, tab_width);
+            expanded.extend(std::iter::repeat_n(' ', next - column));
+            column = next;
+        } else {
+            expanded.push(c);
+            column += c.width().unwrap_or(0);
+        }
+    }
+    expanded
+}
+
+/// Returns the column of the first tab stop after `column`, with tab stops every `tab_width`
+/// columns.
+#[must_use]
+pub fn next_tab_stop(column: usize, tab_width: usize) -> usize {
+    let tab_width = tab_width.max(1);
+    (column / tab_width + 1) * tab_width
+}
+
 /// Iterator over the lines in a string while keeping track of the byte index within the source of
 /// the start of each line.
 pub struct LinesWithPosition<'a> {
@@ -428,11 +502,11 @@ mod test {
     fn function_definition_name() {
         // List of tuples of the form (code, function name)
         let tests = [
-            ("int main() {}", "main"),
-            ("void **(*ptrptrptr)(char a[])", "ptrptrptr"),
-            ("char *strcpy(char *dst, const char *src)", "strcpy"),
-            ("char *strdup(const char *src)", "strdup"),
-            ("void free(void *ptr)", "free"),
+            ("int main() {}", Some("main")),
+            ("void **(*ptrptrptr)(char a[]) {}", Some("ptrptrptr")),
+            ("char *strcpy(char *dst, const char *src) {}", Some("strcpy")),
+            ("int (f)(void) {}", Some("f")),
+            ("DECL(g) {}", None),
         ];
         for (code, expected_name) in tests {
             let mut parser = Parser::new();
@@ -463,6 +537,30 @@ mod test {
         }
     }
 
+    #[test]
+    fn text_width() {
+        let tests = [
+            ("", 0),
+            ("\t", 8),
+            ("\t\t", 16),
+            ("  \t", 8),
+            ("  \t  ", 10),
+            ("ab\tc\t", 16),
+            ("int 😵 = 5;", 11),
+        ];
+        for (text, expected) in tests {
+            assert_eq!(expected, super::text_width(text, 8), "{text:?}");
+        }
+        assert_eq!(6, super::text_width("ab\tc\t", 3));
+    }
+
+    #[test]
+    fn expand_tabs() {
+        assert_eq!("a       b", super::expand_tabs("a\tb", 8));
+        assert_eq!("ab  c   d", super::expand_tabs("ab\tc\td", 4));
+        assert_eq!(" ".repeat(16), super::expand_tabs("        \t", 8));
+    }
+
     #[test]
     fn range_collapser() {
         let code = indoc! {
@@ -0,0 +1,762 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Language Server Protocol (LSP) server, started by `westwood lsp`.
+//!
+//! The server communicates with the editor over standard input and output. Each document is
+//! checked when it is opened and whenever it changes, and the resulting diagnostics are published
+//! to the editor. Hovering over a diagnostic shows the text of the code standard rule it comes
+//! from, and problems which can be fixed mechanically are offered as code actions.
+//!
+//! Each open document keeps its [`Tree`], which is updated using [`Tree::edit()`] and re-parsed
+//! incrementally when the document changes.
+//!
+//! Documents are checked using the configuration file found for them in the same way as on the
+//! command line (see [`config::find_config_file()`]). The configuration is loaded when a document
+//! is opened, so changes to it take effect once the document is re-opened.
+
+use std::{collections::HashMap, error::Error, ops::Range};
+
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, LogMessage,
+        Notification as _, PublishDiagnostics, ShowMessage,
+    },
+    request::{CodeActionRequest, HoverRequest, Request as _},
+    ClientCapabilities, CodeAction, CodeActionKind, CodeActionOptions, CodeActionOrCommand,
+    CodeActionParams, CodeActionProviderCapability, DiagnosticRelatedInformation,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, InitializeResult, Location, LogMessageParams, MarkupContent, MarkupKind,
+    MessageType, NumberOrString, Position, PositionEncodingKind, PublishDiagnosticsParams,
+    ServerCapabilities, ServerInfo, ShowMessageParams, TextDocumentContentChangeEvent,
+    TextDocumentItem, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    WorkspaceEdit,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use tree_sitter::{InputEdit, Parser, Point, Tree};
+
+use crate::{
+    config::{self, Config},
+    fix,
+    helpers::floor_char_boundary,
+    rules::{
+        self,
+        api::{Lint, Rule, RuleCode, SourceInfo},
+        check_source,
+    },
+};
+
+/// Name shown by editors as the source of each diagnostic.
+const SOURCE_NAME: &str = "westwood";
+
+/// Error which stops the server.
+type ServerError = Box<dyn Error + Send + Sync>;
+
+/// Runs the LSP server until the editor asks it to exit.
+pub fn run() -> Result<(), ServerError> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let (id, params) = connection.initialize_start()?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let encoding = PositionEncoding::negotiate(&params.capabilities);
+    let result = InitializeResult {
+        capabilities: ServerCapabilities {
+            position_encoding: Some(encoding.into()),
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                TextDocumentSyncKind::INCREMENTAL,
+            )),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                code_action_kinds: Some(vec![
+                    CodeActionKind::QUICKFIX,
+                    CodeActionKind::SOURCE_FIX_ALL,
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        server_info: Some(ServerInfo {
+            name: SOURCE_NAME.to_owned(),
+            version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+        }),
+    };
+    connection.initialize_finish(id, serde_json::to_value(result)?)?;
+
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_c::LANGUAGE.into())
+        .expect("Error loading C parser grammar");
+    let server = Server {
+        connection,
+        encoding,
+        parser,
+        documents: HashMap::new(),
+    };
+    server.main_loop()?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// State of the LSP server.
+struct Server {
+    connection: Connection,
+    encoding: PositionEncoding,
+    parser: Parser,
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    /// Handles messages until the editor asks the server to shut down.
+    fn main_loop(mut self) -> Result<(), ServerError> {
+        let receiver = self.connection.receiver.clone();
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            HoverRequest::METHOD => respond(request, |params| self.hover(params)),
+            CodeActionRequest::METHOD => respond(request, |params| self.code_actions(params)),
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported request `{}'", request.method),
+            ),
+        }
+    }
+
+    /// Handles a notification from the editor. Notifications with invalid parameters are logged
+    /// and otherwise ignored, since there is no response in which to report the error.
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), ServerError> {
+        let params = notification.params;
+        let result = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => serde_json::from_value(params).map(|p| self.open(p)),
+            DidChangeTextDocument::METHOD => serde_json::from_value(params).map(|p| self.change(p)),
+            DidCloseTextDocument::METHOD => serde_json::from_value(params).map(|p| self.close(p)),
+            _ => Ok(Ok(())),
+        };
+        result.unwrap_or_else(|err| {
+            let params = LogMessageParams {
+                typ: MessageType::ERROR,
+                message: format!(
+                    "Westwood: Ignoring `{}' notification with invalid parameters: {err}",
+                    notification.method
+                ),
+            };
+            self.connection
+                .sender
+                .send(Notification::new(LogMessage::METHOD.to_owned(), params).into())?;
+            Ok(())
+        })
+    }
+
+    /// Parses and checks a newly-opened document.
+    fn open(&mut self, params: DidOpenTextDocumentParams) -> Result<(), ServerError> {
+        let TextDocumentItem {
+            uri, text, version, ..
+        } = params.text_document;
+        let config = self.load_config(&uri)?;
+        let tree = self.parser.parse(&text, None).expect("Failed to parse code");
+        let mut document = Document {
+            text,
+            tree,
+            version,
+            rules: rules::get_rules(&config),
+            report_unused_suppressions: config.report_unused_suppressions,
+            lints: Vec::new(),
+        };
+        document.check();
+        let document = self.documents.entry(uri.clone()).insert_entry(document).into_mut();
+        publish_diagnostics(&self.connection, uri, document, self.encoding)
+    }
+
+    /// Applies changes to a document, then re-parses and re-checks it.
+    fn change(&mut self, params: DidChangeTextDocumentParams) -> Result<(), ServerError> {
+        let uri = params.text_document.uri;
+        let Some(document) = self.documents.get_mut(&uri) else {
+            return Ok(());
+        };
+        for change in params.content_changes {
+            document.apply_change(change, self.encoding, &mut self.parser);
+        }
+        document.version = params.text_document.version;
+        document.tree = self
+            .parser
+            .parse(&document.text, Some(&document.tree))
+            .expect("Failed to parse code");
+        document.check();
+        publish_diagnostics(&self.connection, uri, document, self.encoding)
+    }
+
+    /// Forgets a closed document and clears its diagnostics.
+    fn close(&mut self, params: DidCloseTextDocumentParams) -> Result<(), ServerError> {
+        let uri = params.text_document.uri;
+        self.documents.remove(&uri);
+        let params = PublishDiagnosticsParams::new(uri, Vec::new(), None);
+        self.connection
+            .sender
+            .send(Notification::new(PublishDiagnostics::METHOD.to_owned(), params).into())?;
+        Ok(())
+    }
+
+    /// Returns the configuration with which to check the document at `uri`. If its configuration
+    /// file cannot be loaded, the error is shown to the user and the default configuration is used.
+    fn load_config(&self, uri: &Url) -> Result<Config, ServerError> {
+        let config_path = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| config::find_config_file(path.parent()?));
+        let config = match config_path.map(|path| Config::load(&path)) {
+            Some(Ok(config)) => config,
+            Some(Err(err)) => {
+                let params = ShowMessageParams {
+                    typ: MessageType::ERROR,
+                    message: format!("Westwood: {err}"),
+                };
+                self.connection
+                    .sender
+                    .send(Notification::new(ShowMessage::METHOD.to_owned(), params).into())?;
+                Config::default()
+            }
+            None => Config::default(),
+        };
+        Ok(config)
+    }
+
+    /// Shows the text of the code standard for the rules violated at the hovered position.
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+        let index = LineIndex::new(&document.text, self.encoding);
+        let offset = index.offset(position.position);
+
+        let mut codes: Vec<RuleCode> = Vec::new();
+        let mut range = None;
+        for lint in &document.lints {
+            let Some(label) = primary_label(&lint.diagnostic) else {
+                continue;
+            };
+            let code = lint.diagnostic.code.as_deref().and_then(|code| code.parse().ok());
+            if let Some(code) = code {
+                if label.range.start <= offset
+                    && offset <= label.range.end
+                    && !codes.contains(&code)
+                {
+                    codes.push(code);
+                    range.get_or_insert_with(|| index.range(label.range.clone()));
+                }
+            }
+        }
+
+        let sections: Vec<String> = codes
+            .into_iter()
+            .filter_map(|code| {
+                let rule = document.rules.iter().find(|rule| rule.code() == code)?;
+                let metadata = rule.metadata();
+                Some(format!("**{code}: {}**\n\n```text\n{}```", metadata.title, metadata.standard))
+            })
+            .collect();
+        if sections.is_empty() {
+            return None;
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: sections.join("\n\n---\n\n"),
+            }),
+            range,
+        })
+    }
+
+    /// Offers a quick fix for each fixable problem in the requested range, and an action which
+    /// fixes all problems in the document.
+    fn code_actions(&self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = params.text_document.uri;
+        let Some(document) = self.documents.get(&uri) else {
+            return Vec::new();
+        };
+        let index = LineIndex::new(&document.text, self.encoding);
+        let requested = index.offset(params.range.start)..index.offset(params.range.end);
+        let wanted = |kind: &CodeActionKind| {
+            params.context.only.as_ref().is_none_or(|only| {
+                only.iter().any(|prefix| kind.as_str().starts_with(prefix.as_str()))
+            })
+        };
+
+        let mut actions = Vec::new();
+        if wanted(&CodeActionKind::QUICKFIX) {
+            for lint in &document.lints {
+                let Some(label) = primary_label(&lint.diagnostic) else {
+                    continue;
+                };
+                if lint.fix.is_empty()
+                    || label.range.end < requested.start
+                    || requested.end < label.range.start
+                {
+                    continue;
+                }
+                let edits = lint
+                    .fix
+                    .iter()
+                    .map(|edit| {
+                        TextEdit::new(index.range(edit.range.clone()), edit.replacement.clone())
+                    })
+                    .collect();
+                let action = CodeAction {
+                    title: format!("Fix: {}", lint.diagnostic.message),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![to_lsp_diagnostic(&uri, &lint.diagnostic, &index)]),
+                    edit: Some(workspace_edit(&uri, edits)),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                };
+                actions.push(action.into());
+            }
+        }
+        if wanted(&CodeActionKind::SOURCE_FIX_ALL)
+            && document.lints.iter().any(|lint| !lint.fix.is_empty())
+        {
+            let fixed = fix::fix_code(&document.text, &document.rules);
+            if fixed != document.text {
+                let edit = TextEdit::new(index.range(0..document.text.len()), fixed);
+                let action = CodeAction {
+                    title: "Fix all fixable Westwood problems".to_owned(),
+                    kind: Some(CodeActionKind::SOURCE_FIX_ALL),
+                    edit: Some(workspace_edit(&uri, vec![edit])),
+                    ..Default::default()
+                };
+                actions.push(action.into());
+            }
+        }
+        actions
+    }
+}
+
+/// Deserializes the parameters of `request`, passes them to `handler`, and returns a response
+/// containing the handler's result.
+fn respond<P, R>(request: Request, handler: impl FnOnce(P) -> R) -> Response
+where
+    P: DeserializeOwned,
+    R: Serialize,
+{
+    match serde_json::from_value(request.params) {
+        Ok(params) => Response::new_ok(request.id, handler(params)),
+        Err(err) => Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string()),
+    }
+}
+
+/// Sends the diagnostics for a document to the editor.
+fn publish_diagnostics(
+    connection: &Connection,
+    uri: Url,
+    document: &Document,
+    encoding: PositionEncoding,
+) -> Result<(), ServerError> {
+    let index = LineIndex::new(&document.text, encoding);
+    let diagnostics = document
+        .lints
+        .iter()
+        .map(|lint| to_lsp_diagnostic(&uri, &lint.diagnostic, &index))
+        .collect();
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, Some(document.version));
+    connection
+        .sender
+        .send(Notification::new(PublishDiagnostics::METHOD.to_owned(), params).into())?;
+    Ok(())
+}
+
+/// Returns a workspace edit which applies `edits` to the document at `uri`.
+fn workspace_edit(uri: &Url, edits: Vec<TextEdit>) -> WorkspaceEdit {
+    WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))
+}
+
+/// Returns the first primary label of a diagnostic.
+fn primary_label(diagnostic: &Diagnostic<()>) -> Option<&Label<()>> {
+    diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary)
+}
+
+/// Converts a diagnostic into its LSP equivalent. The diagnostic's range is that of its primary
+/// label, and its secondary labels become related information. Notes are appended to the
+/// message, since LSP has no equivalent for them.
+fn to_lsp_diagnostic(
+    uri: &Url,
+    diagnostic: &Diagnostic<()>,
+    index: &LineIndex,
+) -> lsp_types::Diagnostic {
+    let range = primary_label(diagnostic)
+        .map(|label| index.range(label.range.clone()))
+        .unwrap_or_default();
+    let severity = match diagnostic.severity {
+        Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+        Severity::Note => DiagnosticSeverity::INFORMATION,
+        Severity::Help => DiagnosticSeverity::HINT,
+    };
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+    let related_information: Vec<DiagnosticRelatedInformation> = diagnostic
+        .labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Secondary && !label.message.is_empty())
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), index.range(label.range.clone())),
+            message: label.message.clone(),
+        })
+        .collect();
+    lsp_types::Diagnostic {
+        range,
+        severity: Some(severity),
+        code: diagnostic.code.clone().map(NumberOrString::String),
+        source: Some(SOURCE_NAME.to_owned()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Default::default()
+    }
+}
+
+/// A document opened in the editor.
+struct Document {
+    text: String,
+    /// Syntax tree of `text`.
+    tree: Tree,
+    /// Version number given by the editor, which increases with each change.
+    version: i32,
+    /// Rules with which to check the document.
+    rules: Vec<Box<dyn Rule>>,
+    /// Whether to warn about unused suppression comments.
+    report_unused_suppressions: bool,
+    /// Results of the last check.
+    lints: Vec<Lint>,
+}
+
+impl Document {
+    /// Applies a change to the document's text and edits its tree to match. The tree must be
+    /// re-parsed afterwards.
+    fn apply_change(
+        &mut self,
+        change: TextDocumentContentChangeEvent,
+        encoding: PositionEncoding,
+        parser: &mut Parser,
+    ) {
+        // Changes without a range replace the whole document
+        let Some(range) = change.range else {
+            self.text = change.text;
+            self.tree = parser.parse(&self.text, None).expect("Failed to parse code");
+            return;
+        };
+
+        let index = LineIndex::new(&self.text, encoding);
+        let start_byte = index.offset(range.start);
+        let old_end_byte = index.offset(range.end).max(start_byte);
+        let start_position = index.point(start_byte);
+        let old_end_position = index.point(old_end_byte);
+        self.text.replace_range(start_byte..old_end_byte, &change.text);
+        self.tree.edit(&InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte: start_byte + change.text.len(),
+            start_position,
+            old_end_position,
+            new_end_position: end_point(start_position, &change.text),
+        });
+    }
+
+    /// Checks the document and stores the results.
+    fn check(&mut self) {
+        let source = SourceInfo::with_tree(&self.text, self.tree.clone());
+        self.lints = check_source(&source, &self.rules, self.report_unused_suppressions);
+    }
+}
+
+/// Returns the point at the end of `text` if it were inserted at `start`.
+fn end_point(start: Point, text: &str) -> Point {
+    match text.rfind('\n') {
+        Some(last_newline) => Point {
+            row: start.row + text.matches('\n').count(),
+            column: text.len() - last_newline - 1,
+        },
+        None => Point {
+            row: start.row,
+            column: start.column + text.len(),
+        },
+    }
+}
+
+/// Encoding in which the columns of LSP positions are measured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PositionEncoding {
+    /// Columns count UTF-8 code units, i.e. bytes.
+    Utf8,
+    /// Columns count UTF-16 code units. This is the default in LSP.
+    Utf16,
+}
+
+impl PositionEncoding {
+    /// Chooses UTF-8 if the editor supports it, since that is what we use internally, or UTF-16
+    /// otherwise.
+    fn negotiate(capabilities: &ClientCapabilities) -> Self {
+        let supported = capabilities
+            .general
+            .as_ref()
+            .and_then(|general| general.position_encodings.as_ref());
+        if supported.is_some_and(|encodings| encodings.contains(&PositionEncodingKind::UTF8)) {
+            Self::Utf8
+        } else {
+            Self::Utf16
+        }
+    }
+}
+
+impl From<PositionEncoding> for PositionEncodingKind {
+    fn from(encoding: PositionEncoding) -> Self {
+        match encoding {
+            PositionEncoding::Utf8 => PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => PositionEncodingKind::UTF16,
+        }
+    }
+}
+
+/// Converts between byte offsets into a document and LSP positions.
+struct LineIndex<'a> {
+    text: &'a str,
+    encoding: PositionEncoding,
+    /// Byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str, encoding: PositionEncoding) -> Self {
+        let line_starts =
+            std::iter::once(0).chain(text.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        Self {
+            text,
+            encoding,
+            line_starts,
+        }
+    }
+
+    /// Returns the line containing the given byte offset and the offset at which it starts.
+    fn line_of(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        (line, self.line_starts[line])
+    }
+
+    /// Converts a byte offset to a Tree-sitter point, whose column is measured in bytes.
+    fn point(&self, offset: usize) -> Point {
+        let (row, start) = self.line_of(offset);
+        Point {
+            row,
+            column: offset - start,
+        }
+    }
+
+    /// Converts a byte offset to an LSP position. Offsets inside of a character refer to the
+    /// start of that character.
+    fn position(&self, offset: usize) -> Position {
+        let offset = floor_char_boundary(self.text, offset);
+        let (line, start) = self.line_of(offset);
+        let column = match self.encoding {
+            PositionEncoding::Utf8 => offset - start,
+            PositionEncoding::Utf16 => self.text[start..offset].encode_utf16().count(),
+        };
+        Position::new(to_u32(line), to_u32(column))
+    }
+
+    /// Converts a byte range to an LSP range.
+    fn range(&self, range: Range<usize>) -> lsp_types::Range {
+        lsp_types::Range::new(self.position(range.start), self.position(range.end))
+    }
+
+    /// Converts an LSP position to a byte offset. Positions past the end of a line refer to the
+    /// end of the line, and positions past the last line refer to the end of the document.
+    fn offset(&self, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let line = self.text[start..].split_inclusive('\n').next().unwrap_or_default();
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let column = position.character as usize;
+        let mut units = 0;
+        for (i, c) in line.char_indices() {
+            if units >= column {
+                return start + i;
+            }
+            units += match self.encoding {
+                PositionEncoding::Utf8 => c.len_utf8(),
+                PositionEncoding::Utf16 => c.len_utf16(),
+            };
+        }
+        start + line.len()
+    }
+}
+
+/// Converts a line or column number to the type used by LSP, saturating on overflow.
+fn to_u32(n: usize) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use lsp_server::{Connection, Message, Notification};
+    use lsp_types::{
+        notification::{DidOpenTextDocument, LogMessage, Notification as _},
+        Position, Range, TextDocumentContentChangeEvent, Url,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tree_sitter::Parser;
+
+    use crate::{config::Config, rules::get_rules};
+
+    use super::{to_lsp_diagnostic, Document, LineIndex, PositionEncoding, Server};
+
+    fn parser() -> Parser {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_c::LANGUAGE.into()).unwrap();
+        parser
+    }
+
+    /// Tests that a notification with invalid parameters is logged instead of stopping the server.
+    #[test]
+    fn invalid_notification() {
+        let (connection, client) = Connection::memory();
+        let mut server = Server {
+            connection,
+            encoding: PositionEncoding::Utf16,
+            parser: parser(),
+            documents: HashMap::new(),
+        };
+        let notification = Notification::new(
+            DidOpenTextDocument::METHOD.to_owned(),
+            json!({ "textDocument": { "uri": "file:///test.c" } }),
+        );
+        assert!(server.handle_notification(notification).is_ok());
+        assert!(server.documents.is_empty());
+        let Ok(Message::Notification(log)) = client.receiver.try_recv() else {
+            panic!("Expected a log message");
+        };
+        assert_eq!(LogMessage::METHOD, log.method);
+    }
+
+    #[test]
+    fn line_index() {
+        // 'é' is 2 bytes and 1 UTF-16 unit; '😀' is 4 bytes and 2 UTF-16 units.
+        let text = "ab\n\u{e9}\u{1F600}x\ny";
+        let x = text.find('x').unwrap();
+        let tests = [
+            (PositionEncoding::Utf8, 0, Position::new(0, 0)),
+            (PositionEncoding::Utf8, x, Position::new(1, 6)),
+            (PositionEncoding::Utf16, x, Position::new(1, 3)),
+            (PositionEncoding::Utf16, text.len(), Position::new(2, 1)),
+        ];
+        for (encoding, offset, position) in tests {
+            let index = LineIndex::new(text, encoding);
+            assert_eq!(position, index.position(offset), "{encoding:?}");
+            assert_eq!(offset, index.offset(position), "{encoding:?}");
+        }
+
+        // Out-of-range positions are clamped
+        let index = LineIndex::new(text, PositionEncoding::Utf16);
+        assert_eq!(2, index.offset(Position::new(0, 10)));
+        assert_eq!(text.len(), index.offset(Position::new(10, 0)));
+
+        // Offsets inside of a character are moved to its start
+        for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16] {
+            let index = LineIndex::new(text, encoding);
+            assert_eq!(Position::new(1, 0), index.position(4), "{encoding:?}");
+        }
+    }
+
+    /// Tests that diagnostics for long lines of non-ASCII text can be converted.
+    #[test]
+    fn non_ascii_diagnostics() {
+        let text = format!("/* {} */\nint main() {{\n  return 0;\n}}\n", "\u{e9}".repeat(90));
+        let uri = Url::parse("file:///test.c").unwrap();
+        let mut document = Document {
+            text: text.clone(),
+            tree: parser().parse(&text, None).unwrap(),
+            version: 0,
+            rules: get_rules(&Config::default()),
+            report_unused_suppressions: false,
+            lints: Vec::new(),
+        };
+        document.check();
+        assert!(document
+            .lints
+            .iter()
+            .any(|lint| lint.diagnostic.code.as_deref() == Some("II:A")));
+        for encoding in [PositionEncoding::Utf8, PositionEncoding::Utf16] {
+            let index = LineIndex::new(&document.text, encoding);
+            for lint in &document.lints {
+                to_lsp_diagnostic(&uri, &lint.diagnostic, &index);
+            }
+        }
+    }
+
+    /// Tests that incrementally re-parsing an edited document gives the same result as parsing
+    /// the new text from scratch.
+    #[test]
+    fn apply_change() {
+        let mut parser = parser();
+        let text = "int main() {\n  return 0;\n}\n";
+        let mut document = Document {
+            text: text.to_owned(),
+            tree: parser.parse(text, None).unwrap(),
+            version: 0,
+            rules: Vec::new(),
+            report_unused_suppressions: false,
+            lints: Vec::new(),
+        };
+        let changes = [
+            // Replace `0' with a multi-line expression
+            (Range::new(Position::new(1, 9), Position::new(1, 10)), "foo(\n    1)"),
+            // Insert a declaration before the return statement
+            (Range::new(Position::new(1, 0), Position::new(1, 0)), "  int \u{e9};\n"),
+        ];
+        for (range, text) in changes {
+            let change = TextDocumentContentChangeEvent {
+                range: Some(range),
+                range_length: None,
+                text: text.to_owned(),
+            };
+            document.apply_change(change, PositionEncoding::Utf16, &mut parser);
+        }
+        let expected = "int main() {\n  int \u{e9};\n  return foo(\n    1);\n}\n";
+        assert_eq!(expected, document.text);
+        let tree = parser.parse(&document.text, Some(&document.tree)).unwrap();
+        let fresh = parser.parse(&document.text, None).unwrap();
+        assert_eq!(fresh.root_node().to_sexp(), tree.root_node().to_sexp());
+        assert_eq!(fresh.root_node().byte_range(), tree.root_node().byte_range());
+    }
+}
@@ -13,36 +13,72 @@
 // limitations under the License.
 
 use std::{
-    io::{stdout, IsTerminal},
+    collections::HashMap,
+    fs,
+    io::{self, stdin, stdout, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
     process::ExitCode,
+    rc::Rc,
 };
 
-use clap::{crate_description, Parser as CliArgParser, ValueEnum};
-use clap_stdin::FileOrStdin;
+use clap::{crate_description, Parser as CliArgParser, Subcommand, ValueEnum};
 use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
 use codespan_reporting::{
-    files::{Files, SimpleFile},
+    files::SimpleFiles,
     term::{
         self,
         termcolor::{ColorChoice, StandardStream},
     },
 };
+use config::{Config, ConfigError};
 use crashlog::cargo_metadata;
-use rules::api::SourceInfo;
-use tree_sitter::{Parser, Tree};
+use indoc::indoc;
+use rules::api::{Rule, RuleCode, SourceInfo, Suggestion, WESTWOOD_SECTION};
+use select::RuleSelector;
+use similar::TextDiff;
 
+pub mod config;
+pub mod fix;
 pub mod helpers;
+pub mod lsp;
+pub mod output;
 pub mod rules;
+pub mod select;
+pub mod suppress;
+pub mod syntax;
 
 /// Description printed with `--help` flag
 const LONG_ABOUT: &str = concat!("Westwood: ", crate_description!());
 
+/// Text printed after the options with `--help` flag. Keep in sync with [`Status`].
+const AFTER_HELP: &str = indoc! {"
+    Exit status:
+      0  No problems were found
+      1  Problems were found (see --max-warnings), or --diff printed fixes
+      2  A file could not be read or written, or the command line was invalid
+      3  A file contains syntax errors, so parts of it could not be checked
+
+    If more than one applies, 2 takes precedence over 3, which takes precedence over 1.
+"};
+
 #[derive(CliArgParser, Debug)]
-#[command(version, about = None, long_about = LONG_ABOUT)]
+#[command(
+    version,
+    about = None,
+    long_about = LONG_ABOUT,
+    after_help = AFTER_HELP,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct CliOptions {
-    /// File to lint, or `-` for standard input
-    #[arg()]
-    file: FileOrStdin,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Files or directories to lint, or `-` for standard input
+    ///
+    /// Directories are searched recursively for `.c` and `.h` files.
+    #[arg(required_unless_present_any = ["list_rules", "explain"])]
+    files: Vec<PathBuf>,
 
     /// Format in which to print diagnostics
     #[arg(value_enum, short, long, default_value_t = OutputFormat::Pretty)]
@@ -55,6 +91,116 @@ struct CliOptions {
     /// How to sort diagnostics before printing
     #[arg(value_enum, long, default_value_t = OutputSort::Line)]
     sort: OutputSort,
+
+    /// Fix problems which can be fixed mechanically, modifying files in place
+    ///
+    /// Problems which remain after fixing are reported as usual.
+    #[arg(long)]
+    fix: bool,
+
+    /// Print the fixes that `--fix` would make as a unified diff instead of applying them
+    ///
+    /// The exit status is 1 if any fixes are printed.
+    #[arg(long)]
+    diff: bool,
+
+    /// Configuration file to use for all files
+    ///
+    /// By default, each file is checked using the first `westwood.toml` file found in its
+    /// directory or any of the directory's ancestors.
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Check only the given rules
+    ///
+    /// Each selector is either a rule code like `III:B` or a section like `III`. Overrides rules
+    /// enabled in the configuration file.
+    #[arg(long, value_name = "SELECTOR", value_delimiter = ',')]
+    select: Vec<RuleSelector>,
+
+    /// Do not check the given rules
+    ///
+    /// Accepts the same selectors as `--select`, and takes precedence over it.
+    #[arg(long, value_name = "SELECTOR", value_delimiter = ',')]
+    ignore: Vec<RuleSelector>,
+
+    /// Exit successfully if at most N warnings are found
+    ///
+    /// By default, any warning causes a non-zero exit status. Errors always do.
+    #[arg(long, value_name = "N", conflicts_with = "deny_warnings")]
+    max_warnings: Option<usize>,
+
+    /// Report all warnings as errors
+    #[arg(long)]
+    deny_warnings: bool,
+
+    /// Print a table of all rules and exit
+    ///
+    /// Only rules selected by `--select`/`--ignore` are listed.
+    #[arg(long, conflicts_with_all = ["files", "explain"])]
+    list_rules: bool,
+
+    /// Print the code standard text and implementation notes for a rule and exit
+    #[arg(long, value_name = "CODE", conflicts_with = "files")]
+    explain: Option<RuleCode>,
+}
+
+/// Subcommands, which replace the default behavior of linting files
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run a Language Server Protocol server on standard input/output
+    ///
+    /// The server publishes diagnostics for each open document as it changes, shows the code
+    /// standard for a diagnostic on hover, and offers code actions to fix problems.
+    Lsp,
+}
+
+/// Exit status of the program.
+///
+/// If more than one applies, the one listed first takes precedence.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Status {
+    /// A file could not be read or written, or the command line was invalid. This matches the
+    /// status used by [`clap`] for usage errors.
+    Error = 2,
+
+    /// A file contained syntax errors, so parts of it could not be checked.
+    SyntaxError = 3,
+
+    /// Diagnostics were found (see [`diagnostics_status()`]), or fixes were printed by `--diff`.
+    DiagnosticsFound = 1,
+
+    /// No diagnostics were found.
+    Clean = 0,
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// Configuration which applies to a set of files, along with the rules it enables.
+struct Profile {
+    config: Config,
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Profile {
+    /// Loads the configuration file at the given path, or the default configuration if `path` is
+    /// [`None`]. Only rules selected by the `--select`/`--ignore` options in `cli` are kept, and
+    /// unused suppressions are only reported if their code is selected.
+    fn load(path: Option<&Path>, cli: &CliOptions) -> Result<Self, ConfigError> {
+        let mut config = match path {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        config.report_unused_suppressions &=
+            select::is_selected(suppress::UNUSED_SUPPRESSION, &cli.select, &cli.ignore);
+        let mut rules = rules::get_rules(&config);
+        rules.retain(|rule| select::is_selected(rule.code(), &cli.select, &cli.ignore));
+        Ok(Self { config, rules })
+    }
 }
 
 /// Format in which to print diagnostics
@@ -65,6 +211,34 @@ enum OutputFormat {
 
     /// Machine-parseable output
     Machine,
+
+    /// GCC-style `file:line:column: severity: message` output, understood by most editors and
+    /// build tools
+    Gcc,
+
+    /// GitHub Actions workflow commands, which show diagnostics as annotations
+    Github,
+
+    /// JSON output
+    Json,
+
+    /// SARIF 2.1.0 output, for code scanning tools
+    Sarif,
+
+    /// JUnit XML report with a test suite per file and a test case per rule
+    Junit,
+
+    /// Checkstyle XML report
+    Checkstyle,
+
+    /// Code Climate JSON report, as used for GitLab code quality reports
+    Codeclimate,
+
+    /// Full source listing with each diagnostic printed below the line it refers to
+    Annotated,
+
+    /// Standalone HTML report with highlighted source code
+    Html,
 }
 
 /// How to sort diagnostics
@@ -112,140 +286,452 @@ fn main() -> ExitCode {
 
     let cli = CliOptions::parse();
 
-    // Save filename
-    let filename = if cli.file.is_file() {
-        cli.file.filename()
-    } else {
-        "(stdin)"
+    if let Some(Command::Lsp) = cli.command {
+        return match lsp::run() {
+            Ok(()) => Status::Clean.into(),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                Status::Error.into()
+            }
+        };
     }
-    .to_owned();
 
-    // Read file
-    let code: String = match cli.file.contents() {
-        Ok(contents) => contents,
-        Err(err) => {
-            eprintln!("Error: Cannot read {filename}: {err}");
-            return ExitCode::FAILURE;
+    // Find files to lint
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for path in &cli.files {
+        if let Err(err) = collect_source_files(path, &mut paths) {
+            eprintln!("Error: Cannot read {}: {err}", path.display());
+            return Status::Error.into();
         }
-    };
+    }
 
-    // Create parser
-    let mut parser: Parser = Parser::new();
-    parser
-        .set_language(&tree_sitter_c::LANGUAGE.into())
-        .expect("Error loading C parser grammar");
+    if cli.fix && !cli.diff && paths.iter().any(|path| path.as_os_str() == "-") {
+        eprintln!("Error: Cannot fix standard input in place. Use --diff instead.");
+        return Status::Error.into();
+    }
 
-    // Parse code
-    let tree: Tree = parser.parse(&code, None).expect("Failed to parse code");
+    // Catch typos in rule selectors
+    let all_rules = rules::get_rules(&Config::default());
+    for selector in cli.select.iter().chain(&cli.ignore) {
+        if !all_rules.iter().any(|rule| selector.matches(rule.code()))
+            && !selector.matches(suppress::UNUSED_SUPPRESSION)
+        {
+            eprintln!("Error: No rules match the selector `{selector}'");
+            return Status::Error.into();
+        }
+    }
 
-    // Check for syntax errors
-    if tree.root_node().has_error() {
-        eprintln!("Found syntax error(s) in your code.");
-        eprintln!("Ensure your code compiles before running the linter.");
-        eprintln!("To prevent false positives, the linter will not check code with syntax errors.");
-        return ExitCode::FAILURE;
+    if cli.list_rules {
+        let selected: Vec<&dyn Rule> = all_rules
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|rule| select::is_selected(rule.code(), &cli.select, &cli.ignore))
+            .collect();
+        print_rule_list(&selected);
+        return Status::Clean.into();
     }
 
-    // Create diagnostic writer & file source
-    let writer = StandardStream::stdout(cli.color.into());
-    // TODO: Detect color (and maybe box drawing) support
-    let config = term::Config {
-        tab_width: 8,
-        ..Default::default()
-    };
-    let files = SimpleFile::new(filename, &code);
+    if let Some(code) = cli.explain {
+        let Some(rule) = all_rules.iter().find(|rule| rule.code() == code) else {
+            eprintln!("Error: No rule has the code `{code}'");
+            return Status::Error.into();
+        };
+        print_rule_explanation(rule.as_ref());
+        return Status::Clean.into();
+    }
+
+    // Load configuration for each file. Files sharing a configuration file share a profile.
+    let mut profiles: HashMap<Option<PathBuf>, Rc<Profile>> = HashMap::new();
+    let mut file_profiles: Vec<Rc<Profile>> = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let config_path = cli.config.clone().or_else(|| {
+            let dir = if path.as_os_str() == "-" {
+                Path::new(".")
+            } else {
+                path.parent().unwrap_or(Path::new("."))
+            };
+            config::find_config_file(dir)
+        });
+        let profile = if let Some(profile) = profiles.get(&config_path) {
+            profile.clone()
+        } else {
+            match Profile::load(config_path.as_deref(), &cli) {
+                Ok(profile) => {
+                    let profile = Rc::new(profile);
+                    profiles.insert(config_path, profile.clone());
+                    profile
+                }
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    return Status::Error.into();
+                }
+            }
+        };
+        file_profiles.push(profile);
+    }
+
+    // Read (and possibly fix) files
+    let mut files: SimpleFiles<String, String> = SimpleFiles::new();
+    let mut found_fixes = false;
+    for (path, profile) in std::iter::zip(&paths, &file_profiles) {
+        let (filename, contents) = if path.as_os_str() == "-" {
+            let mut contents = String::new();
+            ("(stdin)".to_owned(), stdin().read_to_string(&mut contents).map(|_| contents))
+        } else {
+            (path.display().to_string(), fs::read_to_string(path))
+        };
+        let mut contents = match contents {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("Error: Cannot read {filename}: {err}");
+                return Status::Error.into();
+            }
+        };
+
+        if cli.fix || cli.diff {
+            let fixed = fix::fix_code(&contents, &profile.rules);
+            if cli.diff {
+                found_fixes |= fixed != contents;
+                print!(
+                    "{}",
+                    TextDiff::from_lines(&contents, &fixed)
+                        .unified_diff()
+                        .header(&filename, &filename)
+                );
+            } else if fixed != contents {
+                if let Err(err) = fs::write(path, &fixed) {
+                    eprintln!("Error: Cannot write {filename}: {err}");
+                    return Status::Error.into();
+                }
+                contents = fixed;
+            }
+        }
+
+        files.add(filename, contents);
+    }
+
+    // In diff mode, only the diffs are printed
+    if cli.diff {
+        return if found_fixes {
+            Status::DiagnosticsFound
+        } else {
+            Status::Clean
+        }
+        .into();
+    }
 
     // Do checks
-    let source = SourceInfo::new(&code);
-    let mut diagnostics: Vec<_> = crate::rules::get_rules()
-        .into_iter()
-        .flat_map(|rule| rule.check(&source))
-        .collect();
+    let mut results: Vec<(Diagnostic<usize>, Vec<Suggestion>)> = Vec::new();
+    let mut found_syntax_error = false;
+    for (file_id, profile) in file_profiles.iter().enumerate() {
+        let file = files.get(file_id).expect("Expected file to exist");
+        let source = SourceInfo::new(file.source());
+        // Syntax errors are reported as diagnostics by check_source()
+        found_syntax_error |= source.tree.root_node().has_error();
+        results.extend(
+            rules::check_source(&source, &profile.rules, profile.config.report_unused_suppressions)
+                .into_iter()
+                .map(|lint| (with_file_id(lint.diagnostic, file_id), lint.suggestions)),
+        );
+    }
+
+    // Create diagnostic writer
+    let writer = StandardStream::stdout(cli.color.into());
+
+    if cli.deny_warnings {
+        for (diagnostic, _) in &mut results {
+            if diagnostic.severity == Severity::Warning {
+                diagnostic.severity = Severity::Error;
+            }
+        }
+    }
 
     // Sort diagnostics
     match cli.sort {
-        OutputSort::Line => diagnostics.sort_by_key(|d| {
+        OutputSort::Line => results.sort_by_key(|(d, _)| {
             d.labels
                 .iter()
                 .find(|label| label.style == LabelStyle::Primary)
-                .map_or(0, |label| label.range.start)
+                .map_or((0, 0), |label| (label.file_id, label.range.start))
         }),
 
-        // Don't need to do anything because the diagnostics are already sorted by rule
+        // Don't need to do anything because the diagnostics are already grouped by file and
+        // sorted by rule within each file
         OutputSort::Rule => (),
     }
 
+    // Most output formats only show the diagnostics, so keep the suggestions separately.
+    // suggestions[i] holds the suggestions for diagnostics[i].
+    let (diagnostics, suggestions): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+
     // Print diagnostics
-    for diagnostic in diagnostics {
-        match cli.format {
-            OutputFormat::Pretty => {
-                term::emit(&mut writer.lock(), &config, &files, &diagnostic)
+    match cli.format {
+        OutputFormat::Pretty => {
+            for (diagnostic, suggestions) in std::iter::zip(&diagnostics, &suggestions) {
+                // Render tabs using the tab width configured for the file
+                let file_id = diagnostic.labels.first().map_or(0, |label| label.file_id);
+                // TODO: Detect color (and maybe box drawing) support
+                let config = term::Config {
+                    tab_width: file_profiles[file_id].config.tab_width,
+                    ..Default::default()
+                };
+                output::pretty::write_pretty(
+                    &mut writer.lock(),
+                    &config,
+                    &files,
+                    diagnostic,
+                    suggestions,
+                )
+                .expect("Failed to write diagnostic");
+            }
+        }
+        OutputFormat::Annotated => {
+            let mut writer = writer.lock();
+            for (file_id, profile) in file_profiles.iter().enumerate() {
+                if file_id > 0 {
+                    writeln!(writer).expect("Failed to write diagnostics");
+                }
+                output::annotated::write_annotated(
+                    &mut writer,
+                    &files,
+                    file_id,
+                    &diagnostics,
+                    profile.config.tab_width,
+                )
+                .expect("Failed to write diagnostics");
+            }
+            // Diagnostics without a location can't be shown in the listing
+            let config = term::Config::default();
+            for diagnostic in diagnostics.iter().filter(|diagnostic| {
+                !diagnostic.labels.iter().any(|label| label.style == LabelStyle::Primary)
+            }) {
+                term::emit(&mut writer, &config, &files, diagnostic)
+                    .expect("Failed to write diagnostic");
+            }
+        }
+        OutputFormat::Machine => {
+            let mut stdout = stdout().lock();
+            for (diagnostic, suggestions) in std::iter::zip(&diagnostics, &suggestions) {
+                output::machine::write_machine_parseable(
+                    &mut stdout,
+                    &files,
+                    diagnostic,
+                    suggestions,
+                )
+                .expect("Failed to write diagnostic");
+            }
+        }
+        OutputFormat::Gcc => {
+            let mut stdout = stdout().lock();
+            for diagnostic in &diagnostics {
+                output::gcc::write_gcc(&mut stdout, &files, diagnostic)
                     .expect("Failed to write diagnostic");
             }
-            OutputFormat::Machine => print_machine_parseable(&files, &diagnostic),
+        }
+        OutputFormat::Github => {
+            let mut stdout = stdout().lock();
+            for diagnostic in &diagnostics {
+                output::github::write_github(&mut stdout, &files, diagnostic)
+                    .expect("Failed to write diagnostic");
+            }
+        }
+        OutputFormat::Json => {
+            output::json::write_json(&mut stdout().lock(), &files, &diagnostics, &suggestions)
+                .expect("Failed to write diagnostics");
+        }
+        OutputFormat::Junit => output::junit::write_junit(
+            &mut stdout().lock(),
+            &files,
+            &diagnostics,
+            file_profiles
+                .iter()
+                .enumerate()
+                .map(|(file_id, profile)| (file_id, profile.rules.as_slice())),
+        )
+        .expect("Failed to write diagnostics"),
+        OutputFormat::Checkstyle => output::checkstyle::write_checkstyle(
+            &mut stdout().lock(),
+            &files,
+            &diagnostics,
+            0..file_profiles.len(),
+        )
+        .expect("Failed to write diagnostics"),
+        OutputFormat::Codeclimate => {
+            output::codeclimate::write_codeclimate(&mut stdout().lock(), &files, &diagnostics)
+                .expect("Failed to write diagnostics");
+        }
+        OutputFormat::Html => output::html::write_html(
+            &mut stdout().lock(),
+            &files,
+            &diagnostics,
+            file_profiles
+                .iter()
+                .enumerate()
+                .map(|(file_id, profile)| (file_id, profile.config.tab_width)),
+            &all_rules,
+        )
+        .expect("Failed to write diagnostics"),
+        OutputFormat::Sarif => {
+            // List all rules, since different files may have different rules enabled
+            output::sarif::write_sarif(&mut stdout().lock(), &files, &diagnostics, &all_rules)
+                .expect("Failed to write diagnostics");
         }
     }
 
-    ExitCode::SUCCESS
+    if found_syntax_error {
+        Status::SyntaxError.into()
+    } else {
+        diagnostics_status(&diagnostics, cli.max_warnings.unwrap_or(0)).into()
+    }
 }
 
-/// Prints a [`Diagnostic`] in a machine-parseable format.
-///
-/// # Format
-///
-/// The format is consistent and self-explanatory, so an example should suffice to describe the format:
-/// ```text
-/// WARNING: [I:D] All top-level declarations must come before function definitions
-///          at hw8_main.c from line 212 column 1 to line 217 column 2
-/// ```
-///
-/// Currently, this format does not print any labels other than the primary one, and discards all
-/// label messages, keeping only the message of the diagnostic itself.
-///
-/// # Panics
+/// Prints a table listing the given rules.
+fn print_rule_list(rules: &[&dyn Rule]) {
+    let code_width = rules.iter().map(|rule| rule.code().to_string().len()).max().unwrap_or(0);
+    println!("{:code_width$}  SEVERITY  FIX  TITLE", "CODE");
+    for rule in rules {
+        let metadata = rule.metadata();
+        println!(
+            "{:code_width$}  {:8}  {:3}  {}",
+            metadata.code.to_string(),
+            output::severity_name(metadata.default_severity),
+            if metadata.fixable { "yes" } else { "no" },
+            metadata.title
+        );
+    }
+}
+
+/// Prints the code standard text, implementation notes, and other details of a rule.
+fn print_rule_explanation(rule: &dyn Rule) {
+    let metadata = rule.metadata();
+    println!("{}: {}", metadata.code, metadata.title);
+    if metadata.code.section == WESTWOOD_SECTION {
+        println!("\nDescription (not quoted from the code standard):\n");
+    } else {
+        println!("\nCode standard:\n");
+    }
+    for line in metadata.standard.lines() {
+        if line.is_empty() {
+            println!();
+        } else {
+            println!("    {line}");
+        }
+    }
+    if let Some(notes) = &metadata.notes {
+        println!("\nImplementation notes:\n\n{notes}");
+    }
+    println!("\nDefault severity: {}", output::severity_name(metadata.default_severity));
+    if metadata.fixable {
+        println!("Problems found by this rule can be fixed automatically using `--fix'.");
+    }
+}
+
+/// Returns the exit status for the given diagnostics. Diagnostics are considered found if there
+/// are any errors or more than `max_warnings` warnings. Notes and help messages are ignored.
+fn diagnostics_status<FileId>(diagnostics: &[Diagnostic<FileId>], max_warnings: usize) -> Status {
+    let count = |severities: &[Severity]| {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| severities.contains(&diagnostic.severity))
+            .count()
+    };
+    if count(&[Severity::Bug, Severity::Error]) > 0 || count(&[Severity::Warning]) > max_warnings {
+        Status::DiagnosticsFound
+    } else {
+        Status::Clean
+    }
+}
+
+/// Adds the source files to lint for the given path to `paths`.
 ///
-/// This function requires that the given diagnostic has at least one [`Label`] with a style
-/// of [`Primary`][2]. If this is not the case, it will panic.
+/// If `path` is a directory, it is searched recursively and all files with a `.c` or `.h`
+/// extension are added, in sorted order. Otherwise, `path` itself is added, so files given
+/// explicitly are linted regardless of their extension. The path `-` refers to standard input.
 ///
-/// This function also panics if the primary label of the given diagnostic has a file ID which is
-/// not in the given [`Files`] database.
+/// # Errors
 ///
-/// [2]: codespan_reporting::diagnostic::LabelStyle::Primary
-fn print_machine_parseable<'files, F>(files: &'files F, diagnostic: &Diagnostic<F::FileId>)
-where
-    F: Files<'files, Name: AsRef<str>>,
-{
-    let primary_label: &Label<_> = diagnostic
-        .labels
-        .iter()
-        .find(|label| label.style == LabelStyle::Primary)
-        .expect("Diagnostic has no primary label");
-    let filename = files
-        .name(primary_label.file_id)
-        .expect("Expected to find a file with the given ID");
-    let byte_range = &primary_label.range;
-    let start = files.location(primary_label.file_id, byte_range.start).unwrap();
-    let end = files.location(primary_label.file_id, byte_range.end).unwrap();
-    let severity = match diagnostic.severity {
-        Severity::Bug => "BUG",
-        Severity::Error => "ERROR",
-        Severity::Warning => "WARNING",
-        Severity::Note => "NOTE",
-        Severity::Help => "HELP",
-    };
-    print!("{severity}: ");
-    if let Some(code) = diagnostic.code.as_ref() {
-        print!("[{code}] ");
-    }
-    println!("{}", diagnostic.message);
-    println!(
-        "{:indent$}at {} from line {} column {} to line {} column {}",
-        "",
-        filename,
-        start.line_number,
-        start.column_number,
-        end.line_number,
-        end.column_number,
-        indent = severity.len() + 2,
-    );
+/// Returns an error if `path` does not exist or a directory cannot be read.
+fn collect_source_files(path: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.as_os_str() == "-" || !fs::metadata(path)?.is_dir() {
+        paths.push(path.to_owned());
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_source_files(&entry, paths)?;
+        } else if entry.extension().is_some_and(|ext| ext == "c" || ext == "h") {
+            paths.push(entry);
+        }
+    }
+    Ok(())
+}
+
+/// Converts a [`Diagnostic`] produced by a [rule][rules::api::Rule], which does not know which
+/// file it came from, into one whose labels refer to the file with the given ID.
+fn with_file_id<FileId: Copy>(diagnostic: Diagnostic<()>, file_id: FileId) -> Diagnostic<FileId> {
+    Diagnostic {
+        severity: diagnostic.severity,
+        code: diagnostic.code,
+        message: diagnostic.message,
+        labels: diagnostic
+            .labels
+            .into_iter()
+            .map(|label| Label {
+                style: label.style,
+                file_id,
+                range: label.range,
+                message: label.message,
+            })
+            .collect(),
+        notes: diagnostic.notes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use codespan_reporting::diagnostic::Diagnostic;
+    use pretty_assertions::assert_eq;
+
+    use super::Status;
+
+    /// Tests that directories are searched recursively for C source/header files, in sorted order,
+    /// and that explicitly-named files are included regardless of their extension.
+    #[test]
+    fn collect_source_files() {
+        let dir = std::env::temp_dir().join(format!("westwood-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        for name in ["b.c", "a.h", "notes.txt", "sub/c.c", "sub/d.o"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        super::collect_source_files(&dir, &mut paths).unwrap();
+        super::collect_source_files(&dir.join("notes.txt"), &mut paths).unwrap();
+        let expected: Vec<PathBuf> = ["a.h", "b.c", "sub/c.c", "notes.txt"]
+            .iter()
+            .map(|name| dir.join(name))
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(expected, paths);
+    }
+
+    /// Tests that errors always count as diagnostics, warnings count only past the limit, and
+    /// notes never count.
+    #[test]
+    fn diagnostics_status() {
+        let warnings: [Diagnostic<()>; 2] = [Diagnostic::warning(), Diagnostic::warning()];
+        let mixed: [Diagnostic<()>; 2] = [Diagnostic::note(), Diagnostic::error()];
+        assert_eq!(Status::Clean, super::diagnostics_status::<()>(&[], 0));
+        assert_eq!(Status::DiagnosticsFound, super::diagnostics_status(&warnings, 0));
+        assert_eq!(Status::DiagnosticsFound, super::diagnostics_status(&warnings, 1));
+        assert_eq!(Status::Clean, super::diagnostics_status(&warnings, 2));
+        assert_eq!(Status::Clean, super::diagnostics_status(&[Diagnostic::<()>::note()], 0));
+        assert_eq!(Status::DiagnosticsFound, super::diagnostics_status(&mixed, 10));
+    }
 }
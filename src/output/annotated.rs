@@ -0,0 +1,308 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Annotated source listing output format.
+//!
+//! Unlike the pretty format, which shows only excerpts of the code around each problem, this
+//! format prints the entire file with line numbers, and puts each diagnostic directly below the
+//! line it refers to:
+//!
+//! ```text
+//! hw5.c
+//!  1 | int main() {
+//!  2 |   return 1+2;
+//!    |           ^ warning[III:B]: Expected a single space on each side of binary operator
+//!  3 | }
+//! ```
+//!
+//! Each label is marked using carets (`^`) for primary labels or dashes (`-`) for secondary
+//! labels. A label spanning several lines is marked from its start to the end of its first line.
+//! The marker of a primary label is followed by the diagnostic's severity, code, and message, and
+//! then by the label's message and the diagnostic's notes. The marker of a secondary label is
+//! followed by the label's message.
+//!
+//! Tabs are expanded to spaces so that the markers line up with the code. Colors are only used to
+//! highlight the line numbers and markers, so the output is just as readable without them.
+
+use std::io;
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    files::Files,
+    term::termcolor::{Color, ColorSpec, WriteColor},
+};
+
+use crate::helpers::{expand_tabs, floor_char_boundary, text_width, LinesWithPosition};
+
+use super::severity_name;
+
+/// Label to print below a line of code.
+struct Annotation {
+    /// Index of the line the label starts on.
+    line: usize,
+    /// Column the label starts at.
+    column: usize,
+    /// Number of columns to mark.
+    width: usize,
+    style: LabelStyle,
+    severity: Severity,
+    /// Severity and code of the diagnostic, printed in color after the marker of a primary label.
+    heading: Option<String>,
+    /// Lines of text to print after the marker.
+    text: Vec<String>,
+}
+
+/// Writes the file with the given ID to `writer`, with the diagnostics whose primary labels are in
+/// that file printed below the lines they refer to.
+///
+/// Tabs are expanded to tab stops every `tab_width` columns. Diagnostics without a primary label
+/// are not printed. See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if the given file ID is not in the given [`Files`] database.
+pub fn write_annotated<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    file_id: F::FileId,
+    diagnostics: &[Diagnostic<F::FileId>],
+    tab_width: usize,
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: WriteColor,
+{
+    let name = files.name(file_id).expect("Expected to find a file with the given ID");
+    let source = files.source(file_id).expect("Expected to find a file with the given ID");
+    let mut lines: Vec<(&str, usize)> = LinesWithPosition::from(source.as_ref()).collect();
+    if lines.is_empty() {
+        lines.push(("", 0));
+    }
+
+    let mut annotations: Vec<Annotation> = Vec::new();
+    for diagnostic in diagnostics {
+        if !diagnostic
+            .labels
+            .iter()
+            .any(|label| label.style == LabelStyle::Primary && label.file_id == file_id)
+        {
+            continue;
+        }
+        for label in diagnostic.labels.iter().filter(|label| label.file_id == file_id) {
+            let line = lines.partition_point(|(_, start)| *start <= label.range.start) - 1;
+            let (text, start) = lines[line];
+            let column_at = |offset: usize| {
+                text_width(&text[..floor_char_boundary(text, offset - start)], tab_width)
+            };
+            let column = column_at(label.range.start);
+            let end_column = column_at(label.range.end.max(label.range.start));
+            let mut annotation = Annotation {
+                line,
+                column,
+                width: end_column.saturating_sub(column).max(1),
+                style: label.style,
+                severity: diagnostic.severity,
+                heading: None,
+                text: Vec::new(),
+            };
+            if label.style == LabelStyle::Primary {
+                let mut heading = severity_name(diagnostic.severity).to_owned();
+                if let Some(code) = &diagnostic.code {
+                    heading = format!("{heading}[{code}]");
+                }
+                annotation.heading = Some(heading + ":");
+                annotation.text.extend(diagnostic.message.lines().map(str::to_owned));
+                annotation.text.extend(label.message.lines().map(str::to_owned));
+                for note in &diagnostic.notes {
+                    for (i, note_line) in note.lines().enumerate() {
+                        let prefix = if i == 0 { "= " } else { "  " };
+                        annotation.text.push(format!("{prefix}{note_line}"));
+                    }
+                }
+            } else {
+                annotation.text.extend(label.message.lines().map(str::to_owned));
+            }
+            annotations.push(annotation);
+        }
+    }
+    annotations.sort_by_key(|annotation| (annotation.line, annotation.column));
+
+    let gutter_width = lines.len().to_string().len();
+    let mut gutter_color = ColorSpec::new();
+    gutter_color.set_fg(Some(Color::Blue)).set_bold(true);
+    let mut annotations = annotations.iter().peekable();
+
+    writer.set_color(ColorSpec::new().set_bold(true))?;
+    write!(writer, "{}", name.as_ref())?;
+    writer.reset()?;
+    writeln!(writer)?;
+    for (index, (text, _)) in lines.iter().enumerate() {
+        writer.set_color(&gutter_color)?;
+        write!(writer, "{:>gutter_width$} |", index + 1)?;
+        writer.reset()?;
+        if text.is_empty() {
+            writeln!(writer)?;
+        } else {
+            writeln!(writer, " {}", expand_tabs(text, tab_width))?;
+        }
+        while let Some(annotation) = annotations.next_if(|annotation| annotation.line == index) {
+            annotation.write(writer, gutter_width, &gutter_color)?;
+        }
+    }
+    Ok(())
+}
+
+impl Annotation {
+    fn write(
+        &self,
+        writer: &mut impl WriteColor,
+        gutter_width: usize,
+        gutter_color: &ColorSpec,
+    ) -> io::Result<()> {
+        let (marker, color) = match self.style {
+            LabelStyle::Primary => ('^', severity_color(self.severity)),
+            LabelStyle::Secondary => ('-', Color::Blue),
+        };
+        let mut marker_color = ColorSpec::new();
+        marker_color.set_fg(Some(color)).set_bold(true);
+
+        write_empty_gutter(writer, gutter_width, gutter_color)?;
+        write!(writer, " {:1$}", "", self.column)?;
+        writer.set_color(&marker_color)?;
+        write!(writer, "{}", marker.to_string().repeat(self.width))?;
+        if let Some(heading) = &self.heading {
+            write!(writer, " {heading}")?;
+        }
+        writer.reset()?;
+        let mut text = self.text.iter();
+        match text.next() {
+            Some(line) => writeln!(writer, " {line}")?,
+            None => writeln!(writer)?,
+        }
+        // Line up the remaining text with the start of the marker's text
+        let indent = self.column + self.width + 1;
+        for line in text {
+            write_empty_gutter(writer, gutter_width, gutter_color)?;
+            writeln!(writer, " {:indent$}{line}", "")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the gutter for a line which contains no code.
+fn write_empty_gutter(
+    writer: &mut impl WriteColor,
+    gutter_width: usize,
+    gutter_color: &ColorSpec,
+) -> io::Result<()> {
+    writer.set_color(gutter_color)?;
+    write!(writer, "{:gutter_width$} |", "")?;
+    writer.reset()
+}
+
+/// Returns the color used for primary labels of the given severity, which matches the colors
+/// used by the pretty format.
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Bug | Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+        Severity::Note => Color::Green,
+        Severity::Help => Color::Cyan,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+        term::termcolor::NoColor,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_annotated() {
+        let mut files = SimpleFiles::new();
+        let code = indoc! {"
+            int x;
+            int main() {
+            \treturn 1+2;
+            }
+            int y;
+        "};
+        let file_id = files.add("test.c", code);
+        let other_file = files.add("other.c", "");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("I:D")
+                .with_message("All top-level declarations must come before function definitions")
+                .with_label(Label::primary(file_id, 35..41).with_message("Declaration occurs here"))
+                .with_label(
+                    Label::secondary(file_id, 7..34).with_message("First function defined here"),
+                )
+                .with_note("A note\nwith two lines"),
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_id, 29..30)),
+            Diagnostic::error()
+                .with_message("In another file")
+                .with_label(Label::primary(other_file, 0..0)),
+        ];
+        let expected = indoc! {"
+            test.c
+            1 | int x;
+            2 | int main() {
+              | ------------ First function defined here
+            3 |         return 1+2;
+              |                 ^ warning[III:B]: Expected a single space on each side of binary operator
+            4 | }
+            5 | int y;
+              | ^^^^^^ warning[I:D]: All top-level declarations must come before function definitions
+              |        Declaration occurs here
+              |        = A note
+              |          with two lines
+        "};
+        let mut output = NoColor::new(Vec::new());
+        super::write_annotated(&mut output, &files, file_id, &diagnostics, 8).unwrap();
+        assert_eq!(expected, String::from_utf8(output.into_inner()).unwrap());
+    }
+
+    /// Tests that labels starting or ending inside of a character are marked from its start. The
+    /// label starts inside of the 39th `é', which is in column 42.
+    #[test]
+    fn non_ascii() {
+        let mut files = SimpleFiles::new();
+        let code = format!("/* {} */\n", "\u{e9}".repeat(90));
+        let file_id = files.add("test.c", code.as_str());
+        let diagnostics = [Diagnostic::warning()
+            .with_code("II:A")
+            .with_message("Line length exceeds 80 columns.")
+            .with_label(Label::primary(file_id, 80..code.len() - 1))];
+        let expected = format!(
+            "test.c\n1 | {}\n  | {}{} warning[II:A]: Line length exceeds 80 columns.\n",
+            code.trim_end(),
+            " ".repeat(41),
+            "^".repeat(55)
+        );
+        let mut output = NoColor::new(Vec::new());
+        super::write_annotated(&mut output, &files, file_id, &diagnostics, 8).unwrap();
+        assert_eq!(expected, String::from_utf8(output.into_inner()).unwrap());
+    }
+}
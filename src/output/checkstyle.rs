@@ -0,0 +1,151 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checkstyle XML output format, as read by tools such as the Jenkins Warnings plugin.
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <checkstyle version="4.3">
+//!   <file name="hw5.c">
+//!     <error line="12" column="8" severity="warning" message="Expected a single space on each side of binary operator" source="westwood.III:B"/>
+//!   </file>
+//! </checkstyle>
+//! ```
+//!
+//! Each checked file has a `<file>` element, even if no problems were found in it. Each
+//! diagnostic becomes an `<error>` element in the file containing its primary label, located at
+//! the start of that label. Columns are counted in Unicode characters.
+//!
+//! - `severity` is `error`, `warning`, or `info`.
+//! - `message` is the diagnostic's message, followed by its notes, each on its own line.
+//! - `source` is `westwood.` followed by the code of the violated rule, or just `westwood` if
+//!   there is none.
+
+use std::io::{self, Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    files::Files,
+};
+
+use super::escape_xml;
+
+/// Writes the given diagnostics to `writer` as a Checkstyle XML report.
+///
+/// `file_ids` gives the ID of each file which was checked. See the
+/// [module-level documentation][self] for details.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any of the given file IDs, or any label of the given diagnostics, refers to a file or
+/// location which is not in the given [`Files`] database.
+pub fn write_checkstyle<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+    file_ids: impl IntoIterator<Item = F::FileId>,
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>, FileId: PartialEq>,
+    W: Write,
+{
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<checkstyle version="4.3">"#)?;
+    for file_id in file_ids {
+        let name = files.name(file_id).expect("Expected to find a file with the given ID");
+        writeln!(writer, r#"  <file name="{}">"#, escape_xml(name.as_ref()))?;
+        for diagnostic in diagnostics {
+            let Some(label) =
+                diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary)
+            else {
+                continue;
+            };
+            if label.file_id != file_id {
+                continue;
+            }
+            let location = files
+                .location(file_id, label.range.start)
+                .expect("Expected label to refer to a valid location");
+            let severity = match diagnostic.severity {
+                Severity::Bug | Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note | Severity::Help => "info",
+            };
+            let mut message = diagnostic.message.clone();
+            for note in &diagnostic.notes {
+                message.push('\n');
+                message.push_str(note);
+            }
+            let source = match &diagnostic.code {
+                Some(code) => format!("westwood.{code}"),
+                None => "westwood".to_owned(),
+            };
+            writeln!(
+                writer,
+                r#"    <error line="{}" column="{}" severity="{severity}" message="{}" source="{}"/>"#,
+                location.line_number,
+                location.column_number,
+                escape_xml(&message),
+                escape_xml(&source),
+            )?;
+        }
+        writeln!(writer, "  </file>")?;
+    }
+    writeln!(writer, "</checkstyle>")
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_checkstyle() {
+        let mut files = SimpleFiles::new();
+        let file_a = files.add("a.c", "int main() {\n  return 1+2;\n}\n");
+        let file_b = files.add("b&c.c", "int main() {\n  return 0;\n}\n");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_a, 23..24))
+                .with_note("A note"),
+            Diagnostic::error()
+                .with_message("Syntax error: expected `;'")
+                .with_label(Label::primary(file_a, 0..0)),
+        ];
+        let expected = indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <checkstyle version="4.3">
+              <file name="a.c">
+                <error line="2" column="11" severity="warning" message="Expected a single space on each side of binary operator&#10;A note" source="westwood.III:B"/>
+                <error line="1" column="1" severity="error" message="Syntax error: expected `;&apos;" source="westwood"/>
+              </file>
+              <file name="b&amp;c.c">
+              </file>
+            </checkstyle>
+        "#};
+        let mut output: Vec<u8> = Vec::new();
+        super::write_checkstyle(&mut output, &files, &diagnostics, [file_a, file_b]).unwrap();
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
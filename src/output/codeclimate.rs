@@ -0,0 +1,284 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [Code Climate][spec] JSON output format, as used by GitLab's code quality reports.
+//!
+//! The output is a JSON array containing an issue for each diagnostic:
+//!
+//! ```json
+//! [
+//!   {
+//!     "type": "issue",
+//!     "check_name": "III:B",
+//!     "description": "Expected a single space on each side of binary operator",
+//!     "categories": ["Style"],
+//!     "severity": "minor",
+//!     "fingerprint": "5f0c4c8cbe1d02a7",
+//!     "location": {
+//!       "path": "hw5.c",
+//!       "lines": { "begin": 12, "end": 12 }
+//!     }
+//!   }
+//! ]
+//! ```
+//!
+//! - `check_name` is the code of the violated rule, or `westwood` if there is none.
+//! - `severity` is `critical` for bugs, `major` for errors, `minor` for warnings, and `info` for
+//!   notes and help messages.
+//! - `location` covers the lines of the diagnostic's primary label. Diagnostics without a primary
+//!   label are omitted, since Code Climate requires a location.
+//!
+//! # Fingerprints
+//!
+//! GitLab compares issues' fingerprints to tell which issues are new and which were fixed, so a
+//! fingerprint must stay the same as long as the problem does, even if the code around it
+//! changes. The fingerprint is therefore a hash of the check name, the file path, and the text of
+//! the lines containing the primary label with all runs of whitespace replaced by a single space.
+//! If several issues in the same file would have the same fingerprint, the occurrence number is
+//! included in the hash of all but the first, so that each fingerprint is unique.
+//!
+//! [spec]: https://github.com/codeclimate/platform/blob/master/spec/analyzers/SPEC.md
+
+use std::{collections::HashMap, io::Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    files::Files,
+};
+use serde::Serialize;
+
+/// Code Climate issue.
+#[derive(Serialize)]
+struct Issue<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    check_name: &'a str,
+    description: &'a str,
+    categories: [&'static str; 1],
+    severity: &'static str,
+    fingerprint: String,
+    location: Location,
+}
+
+/// Location of an issue.
+#[derive(Serialize)]
+struct Location {
+    path: String,
+    lines: Lines,
+}
+
+/// Range of lines, which are 1-based and inclusive.
+#[derive(Serialize)]
+struct Lines {
+    begin: usize,
+    end: usize,
+}
+
+/// Writes the given diagnostics to `writer` as a Code Climate report.
+///
+/// See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any label of the given diagnostics refers to a file or location which is not in the
+/// given [`Files`] database.
+pub fn write_codeclimate<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+) -> std::io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    // Number of times each fingerprint has been seen so far
+    let mut occurrences: HashMap<u64, usize> = HashMap::new();
+    let mut issues: Vec<Issue> = Vec::new();
+    for diagnostic in diagnostics {
+        let Some(label) = diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary)
+        else {
+            continue;
+        };
+        let path = files
+            .name(label.file_id)
+            .expect("Expected to find a file with the given ID")
+            .as_ref()
+            .to_owned();
+        let line_index = |byte_index| {
+            files
+                .line_index(label.file_id, byte_index)
+                .expect("Expected label to refer to a valid location")
+        };
+        // Labels should never be reversed, but if one is, report the lines it spans
+        let (begin, end) = (line_index(label.range.start), line_index(label.range.end));
+        let (begin, end) = (begin.min(end), begin.max(end));
+        let source =
+            files.source(label.file_id).expect("Expected to find a file with the given ID");
+        let line_range = |line_index| {
+            files
+                .line_range(label.file_id, line_index)
+                .expect("Expected label to refer to a valid location")
+        };
+        let snippet = &source.as_ref()[line_range(begin).start..line_range(end).end];
+        let check_name = diagnostic.code.as_deref().unwrap_or("westwood");
+
+        let mut hash = fingerprint(&[check_name, &path, &normalize_whitespace(snippet)]);
+        let occurrence = occurrences.entry(hash).or_insert(0);
+        *occurrence += 1;
+        if *occurrence > 1 {
+            hash = fingerprint(&[&hash.to_string(), &occurrence.to_string()]);
+        }
+
+        issues.push(Issue {
+            kind: "issue",
+            check_name,
+            description: &diagnostic.message,
+            categories: ["Style"],
+            severity: match diagnostic.severity {
+                Severity::Bug => "critical",
+                Severity::Error => "major",
+                Severity::Warning => "minor",
+                Severity::Note | Severity::Help => "info",
+            },
+            fingerprint: format!("{hash:016x}"),
+            location: Location {
+                path,
+                lines: Lines {
+                    begin: begin + 1,
+                    end: end + 1,
+                },
+            },
+        });
+    }
+    serde_json::to_writer_pretty(&mut *writer, &issues)?;
+    writeln!(writer)
+}
+
+/// Replaces each run of whitespace in `text` with a single space and removes leading and
+/// trailing whitespace.
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Hashes the given strings using 64-bit FNV-1a. Unlike [`std::hash::DefaultHasher`], this is
+/// guaranteed to give the same result in every version of Westwood.
+fn fingerprint(parts: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let mut hash = OFFSET_BASIS;
+    for part in parts {
+        // Terminate each part so that e.g. ["ab", "c"] and ["a", "bc"] differ
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use pretty_assertions::{assert_eq, assert_ne};
+    use serde_json::Value;
+
+    /// Returns the fingerprints of the issues produced for the given code, where each diagnostic
+    /// is a III:B warning on the character at the given byte offset.
+    fn fingerprints(code: &str, offsets: &[usize]) -> Vec<String> {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", code);
+        let diagnostics: Vec<_> = offsets
+            .iter()
+            .map(|&offset| {
+                Diagnostic::warning()
+                    .with_code("III:B")
+                    .with_message("Message")
+                    .with_label(Label::primary(file_id, offset..offset + 1))
+            })
+            .collect();
+        let mut output: Vec<u8> = Vec::new();
+        super::write_codeclimate(&mut output, &files, &diagnostics).unwrap();
+        let issues: Value = serde_json::from_slice(&output).unwrap();
+        issues
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|issue| issue["fingerprint"].as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn write_codeclimate() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int main() {\n  return 1+2;\n}\n");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_id, 23..24)),
+            Diagnostic::error().with_message("No location"),
+        ];
+        let mut output: Vec<u8> = Vec::new();
+        super::write_codeclimate(&mut output, &files, &diagnostics).unwrap();
+        let issues: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(1, issues.as_array().unwrap().len());
+        let issue = &issues[0];
+        assert_eq!("issue", issue["type"]);
+        assert_eq!("III:B", issue["check_name"]);
+        assert_eq!("minor", issue["severity"]);
+        assert_eq!("test.c", issue["location"]["path"]);
+        assert_eq!(2, issue["location"]["lines"]["begin"]);
+        assert_eq!(2, issue["location"]["lines"]["end"]);
+    }
+
+    #[test]
+    fn reversed_label() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int x;\nint y;\n");
+        let diagnostics = [Diagnostic::warning()
+            .with_message("Reversed")
+            .with_label(Label::primary(file_id, Range { start: 9, end: 2 }))];
+        let mut output: Vec<u8> = Vec::new();
+        super::write_codeclimate(&mut output, &files, &diagnostics).unwrap();
+        let issues: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(1, issues[0]["location"]["lines"]["begin"]);
+        assert_eq!(2, issues[0]["location"]["lines"]["end"]);
+    }
+
+    /// Tests that fingerprints do not change when code around the issue or the whitespace within
+    /// it changes, and that identical issues get unique fingerprints.
+    #[test]
+    fn fingerprint_stability() {
+        let original = fingerprints("int x = 1+2;\n", &[9]);
+        let moved = fingerprints("\nint y;\n\nint  x = 1+2;\n", &[18]);
+        assert_eq!(original, moved);
+
+        let changed = fingerprints("int x = 1+3;\n", &[9]);
+        assert_ne!(original, changed);
+
+        let duplicated = fingerprints("int x = 1+2;\nint x = 1+2;\n", &[9, 22]);
+        assert_eq!(original[0], duplicated[0]);
+        assert_ne!(duplicated[0], duplicated[1]);
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GCC-style output format.
+//!
+//! Each diagnostic is printed on one line in the format used by GCC and most other compilers,
+//! which editors and build tools such as Vim's `:make`, Emacs' `compile`, and IDE problem matchers
+//! already understand:
+//!
+//! ```text
+//! hw8_main.c:212:1: warning: All top-level declarations must come before function definitions [I:D]
+//! hw8_main.c:20:1: note: First function defined here
+//! hw8_main.c:212:1: note: Move this declaration above the first function
+//! ```
+//!
+//! The location is the start of the diagnostic's primary label. Lines and columns are 1-based,
+//! and columns are counted in Unicode characters. The severity is one of `error`, `warning`, or
+//! `note`, and the code of the violated rule, if any, is given in brackets at the end.
+//!
+//! The diagnostic's line is followed by a `note:` line for each secondary label which has a
+//! message, located at the label, and then a `note:` line for each of the diagnostic's notes,
+//! located at the primary label.
+
+use std::io::{self, Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, LabelStyle, Severity},
+    files::Files,
+};
+
+/// Prefix used in place of a location for diagnostics which have no primary label, like GCC's
+/// `cc1:` prefix.
+const NO_LOCATION: &str = "westwood";
+
+/// Writes a [`Diagnostic`] to `writer` in GCC's format.
+///
+/// See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any label of the given diagnostic has a file ID or location which is not in the
+/// given [`Files`] database.
+pub fn write_gcc<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    let primary_location = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .map_or_else(|| NO_LOCATION.to_owned(), |label| location(files, label));
+    let severity = match diagnostic.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "note",
+    };
+
+    write!(writer, "{primary_location}: {severity}: {}", one_line(&diagnostic.message))?;
+    if let Some(code) = diagnostic.code.as_ref() {
+        write!(writer, " [{code}]")?;
+    }
+    writeln!(writer)?;
+    for label in &diagnostic.labels {
+        if label.style == LabelStyle::Secondary && !label.message.is_empty() {
+            writeln!(writer, "{}: note: {}", location(files, label), one_line(&label.message))?;
+        }
+    }
+    for note in &diagnostic.notes {
+        writeln!(writer, "{primary_location}: note: {}", one_line(note))?;
+    }
+    Ok(())
+}
+
+/// Formats the start of a label as `file:line:column`.
+fn location<'files, F>(files: &'files F, label: &Label<F::FileId>) -> String
+where
+    F: Files<'files, Name: AsRef<str>>,
+{
+    let filename = files.name(label.file_id).expect("Expected to find a file with the given ID");
+    let start = files
+        .location(label.file_id, label.range.start)
+        .expect("Expected label to refer to a valid location");
+    format!("{}:{}:{}", filename.as_ref(), start.line_number, start.column_number)
+}
+
+/// Joins the lines of a message with spaces, since each record must fit on one line.
+fn one_line(message: &str) -> String {
+    message.lines().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    /// Tests that secondary labels and notes become `note:` lines.
+    #[test]
+    fn write_gcc() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int x;\nint main() {\n}\nint y;\n");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("I:D")
+                .with_message("All top-level declarations must come before function definitions")
+                .with_label(Label::primary(file_id, 22..28).with_message("Declaration occurs here"))
+                .with_label(
+                    Label::secondary(file_id, 7..21).with_message("First function defined here"),
+                )
+                .with_label(Label::secondary(file_id, 0..6))
+                .with_note("A note\nwith two lines"),
+            Diagnostic::error().with_message("Syntax error"),
+        ];
+        let expected = indoc! {"
+            test.c:4:1: warning: All top-level declarations must come before function definitions [I:D]
+            test.c:2:1: note: First function defined here
+            test.c:4:1: note: A note with two lines
+            westwood: error: Syntax error
+        "};
+        let mut output: Vec<u8> = Vec::new();
+        for diagnostic in &diagnostics {
+            super::write_gcc(&mut output, &files, diagnostic).unwrap();
+        }
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! GitHub Actions [workflow command][commands] output format.
+//!
+//! When run in a GitHub Actions workflow, each diagnostic is shown as an annotation on the line of
+//! code it refers to, both in the workflow run's summary and in pull requests. Each diagnostic
+//! becomes one command:
+//!
+//! ```text
+//! ::warning file=hw5.c,line=12,col=8,endLine=12,endColumn=11,title=[III%3AB]::Expected a single space on each side of binary operator
+//! ```
+//!
+//! - The command is `error`, `warning`, or `notice`, depending on the diagnostic's severity.
+//! - The location is that of the diagnostic's primary label, and is omitted if it has none.
+//!   Columns are counted in Unicode characters.
+//! - The title is the code of the violated rule in brackets, and is omitted if there is none.
+//! - The message is followed by the diagnostic's notes, each on its own line.
+//!
+//! Newlines and other special characters are percent-encoded as GitHub requires, so the title
+//! above is shown as `[III:B]`.
+//!
+//! [commands]: https://docs.github.com/en/actions/reference/workflows-and-actions/workflow-commands
+
+use std::io::{self, Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    files::Files,
+};
+
+/// Writes a [`Diagnostic`] to `writer` as a GitHub Actions workflow command.
+///
+/// See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if the primary label of the given diagnostic has a file ID or location which is not in
+/// the given [`Files`] database.
+pub fn write_github<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    let command = match diagnostic.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "notice",
+    };
+
+    let mut properties: Vec<(&str, String)> = Vec::new();
+    if let Some(label) = diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary) {
+        let filename =
+            files.name(label.file_id).expect("Expected to find a file with the given ID");
+        let start = files
+            .location(label.file_id, label.range.start)
+            .expect("Expected label to refer to a valid location");
+        let end = files
+            .location(label.file_id, label.range.end)
+            .expect("Expected label to refer to a valid location");
+        properties.extend([
+            ("file", filename.as_ref().to_owned()),
+            ("line", start.line_number.to_string()),
+            ("col", start.column_number.to_string()),
+            ("endLine", end.line_number.to_string()),
+            ("endColumn", end.column_number.to_string()),
+        ]);
+    }
+    if let Some(code) = diagnostic.code.as_ref() {
+        properties.push(("title", format!("[{code}]")));
+    }
+
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    write!(writer, "::{command}")?;
+    for (i, (name, value)) in properties.iter().enumerate() {
+        let separator = if i == 0 { ' ' } else { ',' };
+        write!(writer, "{separator}{name}={}", escape_property(value))?;
+    }
+    writeln!(writer, "::{}", escape_data(&message))
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escapes the value of a workflow command's property, which additionally must not contain the
+/// characters used to separate properties.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn write_github() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("dir,1/test.c", "int main() {\n  return 1+2;\n}\n");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_id, 23..24))
+                .with_note("100% of operators\nneed spaces"),
+            Diagnostic::note().with_message("Something: without a location"),
+            Diagnostic::bug().with_message("Bug"),
+        ];
+        let expected = indoc! {"
+            ::warning file=dir%2C1/test.c,line=2,col=11,endLine=2,endColumn=12,title=[III%3AB]::Expected a single space on each side of binary operator%0A100%25 of operators%0Aneed spaces
+            ::notice::Something: without a location
+            ::error::Bug
+        "};
+        let mut output: Vec<u8> = Vec::new();
+        for diagnostic in &diagnostics {
+            super::write_github(&mut output, &files, diagnostic).unwrap();
+        }
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
@@ -0,0 +1,595 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Standalone HTML report output format.
+//!
+//! The report is a single HTML document with its styles embedded, so it can be saved or attached
+//! to a grade without depending on any other files. It contains:
+//!
+//! 1. A summary table giving the number of diagnostics for each violated rule.
+//! 2. For each checked file, a list of its diagnostics, followed by its full source code with
+//!    line numbers. The code is syntax-highlighted based on the kinds of the nodes in its syntax
+//!    tree, and the code covered by each label is highlighted. Hovering over highlighted code
+//!    shows the diagnostic's message.
+//! 3. The text of the code standard for each rule in the summary table, which the table and the
+//!    diagnostic lists link to.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, Write},
+    ops::{Bound, Range},
+};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle},
+    files::Files,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{
+    helpers::{ceil_char_boundary, floor_char_boundary, LinesWithPosition},
+    rules::api::{Rule, SourceInfo},
+};
+
+use super::{escape_xml, rule_code, severity_name};
+
+/// Styles embedded in the report.
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #1f2328; }
+h1, h2, h3 { font-weight: 600; }
+a { color: #0969da; }
+table.summary { border-collapse: collapse; }
+table.summary th, table.summary td { border: 1px solid #d0d7de; padding: 0.25em 0.75em; }
+table.summary td.count { text-align: right; }
+ul.diagnostics { font-family: monospace; }
+pre.standard { background: #f6f8fa; padding: 1em; white-space: pre-wrap; }
+table.source { border-collapse: collapse; font-family: monospace; white-space: pre; }
+table.source td { padding: 0 0.5em; vertical-align: top; }
+td.ln { text-align: right; user-select: none; border-right: 1px solid #d0d7de; }
+td.ln a { color: #6e7781; text-decoration: none; }
+tr:target { background: #fff8c5; }
+.hl-comment { color: #6e7781; font-style: italic; }
+.hl-string { color: #0a3069; }
+.hl-number, .hl-constant { color: #0550ae; }
+.hl-keyword { color: #cf222e; }
+.hl-type { color: #953800; }
+.hl-function { color: #8250df; }
+.hl-field { color: #116329; }
+.hl-preprocessor { color: #cf222e; font-weight: 600; }
+mark { color: inherit; background: none; text-decoration: underline wavy; cursor: help; }
+mark.bug, mark.error { background: #ffebe9; text-decoration-color: #cf222e; }
+mark.warning { background: #fff8c5; text-decoration-color: #bf8700; }
+mark.note, mark.help { background: #ddf4ff; text-decoration-color: #0969da; }
+mark.secondary { background: none; text-decoration-style: dotted; }
+mark.point { border-left: 2px solid; margin-right: -2px; text-decoration: none; }
+li.bug, li.error { color: #cf222e; }
+li.warning { color: #9a6700; }
+";
+
+/// Highlighted label.
+struct Mark {
+    range: Range<usize>,
+    class: String,
+    title: String,
+}
+
+/// Writes an HTML report to `writer`.
+///
+/// `checked_files` gives the ID of each file to include in the report, along with the number of
+/// columns per tab stop to use when showing its code. `rules` are the rules whose text may be
+/// included. See the [module-level documentation][self] for details.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any of the given file IDs, or any label of the given diagnostics, refers to a file or
+/// location which is not in the given [`Files`] database.
+pub fn write_html<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+    checked_files: impl IntoIterator<Item = (F::FileId, usize)>,
+    rules: &[Box<dyn Rule>],
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    let checked_files: Vec<(F::FileId, usize)> = checked_files.into_iter().collect();
+    let title = match checked_files.as_slice() {
+        [(file_id, _)] => format!("Westwood report for {}", file_name(files, *file_id)),
+        _ => "Westwood report".to_owned(),
+    };
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, r#"<html lang="en">"#)?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, r#"<meta charset="utf-8">"#)?;
+    writeln!(writer, "<title>{}</title>", escape_xml(&title))?;
+    writeln!(writer, "<style>{STYLE}</style>")?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>{}</h1>", escape_xml(&title))?;
+
+    // Summary
+    let violated_rules: Vec<(&dyn Rule, usize)> = rules
+        .iter()
+        .map(|rule| {
+            let count = diagnostics
+                .iter()
+                .filter(|diagnostic| rule_code(diagnostic) == Some(rule.code()))
+                .count();
+            (&**rule, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let other_count =
+        diagnostics.len() - violated_rules.iter().map(|(_, count)| count).sum::<usize>();
+    writeln!(writer, "<h2>Summary</h2>")?;
+    if diagnostics.is_empty() {
+        writeln!(writer, "<p>No problems found.</p>")?;
+    } else {
+        writeln!(writer, r#"<table class="summary">"#)?;
+        writeln!(writer, "<tr><th>Rule</th><th>Title</th><th>Count</th></tr>")?;
+        for (rule, count) in &violated_rules {
+            writeln!(
+                writer,
+                r##"<tr><td><a href="#{}">{}</a></td><td>{}</td><td class="count">{count}</td></tr>"##,
+                rule_anchor(&rule.code().to_string()),
+                rule.code(),
+                escape_xml(rule.title()),
+            )?;
+        }
+        if other_count > 0 {
+            writeln!(
+                writer,
+                r#"<tr><td></td><td>Other diagnostics</td><td class="count">{other_count}</td></tr>"#
+            )?;
+        }
+        writeln!(
+            writer,
+            r#"<tr><th colspan="2">Total</th><td class="count">{}</td></tr>"#,
+            diagnostics.len()
+        )?;
+        writeln!(writer, "</table>")?;
+    }
+
+    // Files
+    let rule_sections: Vec<String> =
+        violated_rules.iter().map(|(rule, _)| rule.code().to_string()).collect();
+    for (index, (file_id, tab_width)) in checked_files.iter().enumerate() {
+        write_file(
+            writer,
+            files,
+            *file_id,
+            diagnostics,
+            &format!("f{index}"),
+            *tab_width,
+            &rule_sections,
+        )?;
+    }
+
+    // Rule text
+    if !violated_rules.is_empty() {
+        writeln!(writer, "<h2>Rules</h2>")?;
+        for (rule, _) in &violated_rules {
+            let metadata = rule.metadata();
+            writeln!(
+                writer,
+                r#"<h3 id="{}">{}: {}</h3>"#,
+                rule_anchor(&metadata.code.to_string()),
+                metadata.code,
+                escape_xml(metadata.title),
+            )?;
+            writeln!(writer, r#"<pre class="standard">{}</pre>"#, escape_html(&metadata.standard))?;
+        }
+    }
+
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")
+}
+
+/// Writes the section of the report for one file. The IDs of elements in the section are prefixed
+/// with `id`. Diagnostic codes are linked to the rule text only if they're in `rule_sections`, the
+/// codes of the rules whose text is included in the report.
+fn write_file<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    file_id: F::FileId,
+    diagnostics: &[Diagnostic<F::FileId>],
+    id: &str,
+    tab_width: usize,
+    rule_sections: &[String],
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    let source = files.source(file_id).expect("Expected to find a file with the given ID");
+    let source = source.as_ref();
+    let lines: Vec<(&str, usize)> = LinesWithPosition::from(source).collect();
+    // Returns the 0-based line index and the offset within the line's text closest to the given
+    // byte offset, skipping over line terminators.
+    let line_of = |offset: usize| {
+        let line = lines.partition_point(|(_, start)| *start <= offset).saturating_sub(1);
+        let (text, start) = lines.get(line).copied().unwrap_or(("", 0));
+        (line, offset.clamp(start, start + text.len()))
+    };
+
+    writeln!(writer, r#"<h2 id="{id}">{}</h2>"#, escape_xml(&file_name(files, file_id)))?;
+
+    // List of diagnostics, and the marks to highlight in the code
+    let mut marks: Vec<Mark> = Vec::new();
+    let mut items: Vec<String> = Vec::new();
+    for diagnostic in diagnostics {
+        let Some(primary) = diagnostic
+            .labels
+            .iter()
+            .find(|label| label.style == LabelStyle::Primary && label.file_id == file_id)
+        else {
+            continue;
+        };
+        let severity = severity_name(diagnostic.severity);
+        let mut heading = severity.to_owned();
+        if let Some(code) = &diagnostic.code {
+            heading = format!("{heading}[{code}]");
+        }
+        let mut title = format!("{heading}: {}", diagnostic.message);
+        for note in &diagnostic.notes {
+            title.push('\n');
+            title.push_str(note);
+        }
+
+        let location = files
+            .location(file_id, primary.range.start)
+            .expect("Expected label to refer to a valid location");
+        let (line, _) = line_of(primary.range.start);
+        let code = match &diagnostic.code {
+            Some(code) if rule_sections.contains(code) => {
+                format!(r##"[<a href="#{}">{}</a>]"##, rule_anchor(code), escape_xml(code))
+            }
+            Some(code) => format!("[{}]", escape_xml(code)),
+            None => String::new(),
+        };
+        items.push(format!(
+            r##"<li class="{severity}"><a href="#{id}-L{}">{}:{}</a> <strong>{severity}{code}</strong>: {}</li>"##,
+            line + 1,
+            location.line_number,
+            location.column_number,
+            escape_html(&diagnostic.message),
+        ));
+
+        for label in diagnostic.labels.iter().filter(|label| label.file_id == file_id) {
+            let mut class = severity.to_owned();
+            let mut mark_title = title.clone();
+            if label.style == LabelStyle::Secondary {
+                class.push_str(" secondary");
+                if !label.message.is_empty() {
+                    mark_title = format!("{}\n({title})", label.message);
+                }
+            } else if !label.message.is_empty() {
+                mark_title = format!("{title}\n{}", label.message);
+            }
+            // Mark whole characters, even if the label is reversed
+            let (low, high) =
+                (label.range.start.min(label.range.end), label.range.start.max(label.range.end));
+            let (_, start) = line_of(floor_char_boundary(source, low));
+            let range = if low == high {
+                class.push_str(" point");
+                start..start
+            } else {
+                start..ceil_char_boundary(source, high)
+            };
+            marks.push(Mark {
+                range,
+                class,
+                title: mark_title,
+            });
+        }
+    }
+    if !items.is_empty() {
+        writeln!(writer, r#"<ul class="diagnostics">"#)?;
+        for item in &items {
+            writeln!(writer, "{item}")?;
+        }
+        writeln!(writer, "</ul>")?;
+    }
+
+    // Syntax highlighting classes of each byte
+    let tree = SourceInfo::new(source).tree;
+    let mut classes: Vec<Option<&str>> = vec![None; source.len()];
+    for (range, class) in highlights(&tree) {
+        classes[range].fill(Some(class));
+    }
+
+    // Offsets at which a highlight or mark starts or ends
+    let mut boundaries: BTreeSet<usize> = marks
+        .iter()
+        .flat_map(|mark| [mark.range.start, mark.range.end])
+        .chain((1..source.len()).filter(|&i| classes[i] != classes[i - 1]))
+        .collect();
+
+    writeln!(writer, r#"<table class="source" style="tab-size: {tab_width}">"#)?;
+    for (index, (text, start)) in lines.iter().enumerate() {
+        let line_range = *start..(start + text.len());
+        boundaries.insert(line_range.end);
+        write!(
+            writer,
+            r##"<tr id="{id}-L{0}"><td class="ln"><a href="#{id}-L{0}">{0}</a></td><td>"##,
+            index + 1
+        )?;
+        let mut segment_start = line_range.start;
+        let segment_ends = (Bound::Excluded(line_range.start), Bound::Included(line_range.end));
+        for &segment_end in boundaries.range(segment_ends) {
+            write_points(writer, &marks, segment_start)?;
+            let segment = segment_start..segment_end;
+            let mut covering: Vec<&Mark> = marks
+                .iter()
+                .filter(|mark| mark.range.start <= segment.start && segment.end <= mark.range.end)
+                .collect();
+            // Open the largest marks first, so that hovering shows the most specific message
+            covering.sort_by_key(|mark| std::cmp::Reverse(mark.range.len()));
+            for mark in &covering {
+                write!(
+                    writer,
+                    r#"<mark class="{}" title="{}">"#,
+                    mark.class,
+                    escape_xml(&mark.title)
+                )?;
+            }
+            match classes[segment_start] {
+                Some(class) => write!(
+                    writer,
+                    r#"<span class="hl-{class}">{}</span>"#,
+                    escape_html(&source[segment])
+                )?,
+                None => write!(writer, "{}", escape_html(&source[segment]))?,
+            }
+            for _ in &covering {
+                write!(writer, "</mark>")?;
+            }
+            segment_start = segment_end;
+        }
+        write_points(writer, &marks, line_range.end)?;
+        writeln!(writer, "</td></tr>")?;
+    }
+    writeln!(writer, "</table>")
+}
+
+/// Writes the empty marks located at the given offset.
+fn write_points(writer: &mut impl Write, marks: &[Mark], offset: usize) -> io::Result<()> {
+    for mark in marks.iter().filter(|mark| mark.range.is_empty() && mark.range.start == offset) {
+        write!(
+            writer,
+            r#"<mark class="{}" title="{}"></mark>"#,
+            mark.class,
+            escape_xml(&mark.title)
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the ranges of code to syntax-highlight, along with the class to highlight each with.
+/// The returned ranges do not overlap.
+fn highlights(tree: &Tree) -> Vec<(Range<usize>, &'static str)> {
+    let mut highlights = Vec::new();
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let class = highlight_class(node);
+        if let Some(class) = class {
+            highlights.push((node.byte_range(), class));
+        }
+        // Highlighted nodes are highlighted as a whole, so we don't visit their children
+        if class.is_none() && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return highlights;
+            }
+        }
+    }
+}
+
+/// Returns the class to highlight a node with, based on its kind.
+fn highlight_class(node: Node) -> Option<&'static str> {
+    let is_word = |kind: &str| kind.chars().all(|c| c.is_ascii_alphabetic() || c == '_');
+    match node.kind() {
+        "comment" => Some("comment"),
+        "string_literal" | "char_literal" | "system_lib_string" => Some("string"),
+        "number_literal" => Some("number"),
+        "true" | "false" | "null" => Some("constant"),
+        "primitive_type" | "type_identifier" => Some("type"),
+        "field_identifier" => Some("field"),
+        "preproc_directive" => Some("preprocessor"),
+        "identifier"
+            if node.parent().is_some_and(|parent| {
+                matches!(parent.kind(), "function_declarator" | "call_expression")
+                    && parent.child(0) == Some(node)
+            }) =>
+        {
+            Some("function")
+        }
+        kind if !node.is_named() && kind.starts_with('#') => Some("preprocessor"),
+        kind if !node.is_named() && is_word(kind) => Some("keyword"),
+        _ => None,
+    }
+}
+
+/// Escapes text for use in HTML element content. Unlike [`escape_xml`], this keeps newlines.
+fn escape_html(text: &str) -> String {
+    escape_xml(text).replace("&#10;", "\n")
+}
+
+/// Returns the ID of the element containing a rule's text.
+fn rule_anchor(code: &str) -> String {
+    format!("rule-{}", escape_xml(code))
+}
+
+fn file_name<'files, F>(files: &'files F, file_id: F::FileId) -> String
+where
+    F: Files<'files, Name: AsRef<str>>,
+{
+    files
+        .name(file_id)
+        .expect("Expected to find a file with the given ID")
+        .as_ref()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{config::Config, rules::api::SourceInfo, rules::get_rules};
+
+    #[test]
+    fn write_html() {
+        let mut files = SimpleFiles::new();
+        let code = "int main() {\n  return 1+2; // <done>\n}";
+        let file_id = files.add("test.c", code);
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_id, 23..24))
+                .with_note("A note"),
+            Diagnostic::error()
+                .with_message("Syntax error")
+                .with_label(Label::primary(file_id, 38..38)),
+        ];
+        let rules = get_rules(&Config::default());
+        let mut output: Vec<u8> = Vec::new();
+        super::write_html(&mut output, &files, &diagnostics, [(file_id, 4)], &rules).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        // Summary
+        assert!(output.contains(r##"<tr><td><a href="#rule-III:B">III:B</a></td><td>Put one space on each side of binary operators</td><td class="count">1</td></tr>"##));
+        assert!(output
+            .contains(r#"<tr><td></td><td>Other diagnostics</td><td class="count">1</td></tr>"#));
+        // Diagnostic list
+        assert!(output.contains(r##"<li class="warning"><a href="#f0-L2">2:11</a> <strong>warning[<a href="#rule-III:B">III:B</a>]</strong>: Expected a single space on each side of binary operator</li>"##));
+        // Highlighted code
+        assert!(output.contains(concat!(
+            r##"<tr id="f0-L2"><td class="ln"><a href="#f0-L2">2</a></td><td>  "##,
+            r#"<span class="hl-keyword">return</span> <span class="hl-number">1</span>"#,
+            r#"<mark class="warning" title="warning[III:B]: Expected a single space on each side of binary operator&#10;A note">+</mark>"#,
+            r#"<span class="hl-number">2</span>; <span class="hl-comment">// &lt;done&gt;</span></td></tr>"#,
+        )));
+        assert!(output.contains(r#"<mark class="error point" title="error: Syntax error"></mark>"#));
+        // Rule text
+        assert!(output.contains(
+            r#"<h3 id="rule-III:B">III:B: Put one space on each side of binary operators</h3>"#
+        ));
+        assert!(!output.contains(r#"<h3 id="rule-I:A">"#));
+    }
+
+    /// Tests that codes without rule text in the report, such as W:A, aren't linked.
+    #[test]
+    fn unlinked_codes() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int x; // westwood-ignore-line I:A\n");
+        let diagnostics = [Diagnostic::warning()
+            .with_code("W:A")
+            .with_message("Unused suppression")
+            .with_label(Label::primary(file_id, 31..34))];
+        let rules = get_rules(&Config::default());
+        let mut output: Vec<u8> = Vec::new();
+        super::write_html(&mut output, &files, &diagnostics, [(file_id, 4)], &rules).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("<strong>warning[W:A]</strong>: Unused suppression</li>"));
+        assert!(!output.contains("#rule-"));
+    }
+
+    /// Tests that empty lines, including ones containing a diagnostic, are written.
+    #[test]
+    fn empty_lines() {
+        let mut files = SimpleFiles::new();
+        let code = "int x;\n\nint y;\n";
+        let file_id = files.add("test.c", code);
+        let diagnostics = [Diagnostic::warning()
+            .with_message("Empty")
+            .with_label(Label::primary(file_id, 7..7))];
+        let mut output: Vec<u8> = Vec::new();
+        super::write_html(&mut output, &files, &diagnostics, [(file_id, 4)], &[]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(concat!(
+            r##"<tr id="f0-L2"><td class="ln"><a href="#f0-L2">2</a></td><td>"##,
+            r#"<mark class="warning point" title="warning: Empty"></mark></td></tr>"#,
+        )));
+        assert!(
+            output.contains(r##"<tr id="f0-L3"><td class="ln"><a href="#f0-L3">3</a></td><td>"##)
+        );
+    }
+
+    /// Tests that labels which are reversed or start or end inside of a character mark whole
+    /// characters.
+    #[test]
+    fn non_ascii() {
+        let mut files = SimpleFiles::new();
+        let code = "/* \u{e9}\u{e9}\u{e9} */\n";
+        let file_id = files.add("test.c", code);
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_message("A")
+                .with_label(Label::primary(file_id, 4..6)),
+            Diagnostic::warning()
+                .with_message("B")
+                .with_label(Label::primary(file_id, Range { start: 8, end: 7 })),
+        ];
+        let mut output: Vec<u8> = Vec::new();
+        super::write_html(&mut output, &files, &diagnostics, [(file_id, 4)], &[]).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(concat!(
+            "<span class=\"hl-comment\">/* </span>",
+            "<mark class=\"warning\" title=\"warning: A\">",
+            "<span class=\"hl-comment\">\u{e9}\u{e9}</span></mark>",
+            "<mark class=\"warning\" title=\"warning: B\">",
+            "<span class=\"hl-comment\">\u{e9}</span></mark>",
+            "<span class=\"hl-comment\"> */</span>",
+        )));
+    }
+
+    #[test]
+    fn highlights() {
+        let code = "#include <stdio.h>\nint main() { printf(\"%d\", s.x); }\n";
+        let tree = SourceInfo::new(code).tree;
+        let highlights: Vec<(&str, &str)> = super::highlights(&tree)
+            .into_iter()
+            .map(|(range, class)| (&code[range], class))
+            .collect();
+        assert_eq!(
+            vec![
+                ("#include", "preprocessor"),
+                ("<stdio.h>", "string"),
+                ("int", "type"),
+                ("main", "function"),
+                ("printf", "function"),
+                ("\"%d\"", "string"),
+                ("x", "field"),
+            ],
+            highlights
+        );
+    }
+}
@@ -0,0 +1,367 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON output format.
+//!
+//! # Schema
+//!
+//! The output is a single JSON object containing a schema version and a list of all diagnostics
+//! produced. The version number will be incremented whenever a backwards-incompatible change is
+//! made to the schema. Adding new fields is not considered a backwards-incompatible change.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "diagnostics": [
+//!     {
+//!       "code": "III:B",
+//!       "severity": "warning",
+//!       "message": "Expected a single space on each side of binary operator",
+//!       "file": "hw5.c",
+//!       "labels": [
+//!         {
+//!           "style": "primary",
+//!           "file": "hw5.c",
+//!           "message": "",
+//!           "byte_range": { "start": 215, "end": 218 },
+//!           "start": { "line": 12, "column": 8 },
+//!           "end": { "line": 12, "column": 11 }
+//!         }
+//!       ],
+//!       "notes": [],
+//!       "suggestions": [
+//!         {
+//!           "message": "Put one space on each side of the operator",
+//!           "replacement": " + ",
+//!           "file": "hw5.c",
+//!           "byte_range": { "start": 216, "end": 217 },
+//!           "start": { "line": 12, "column": 9 },
+//!           "end": { "line": 12, "column": 10 }
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! ## Diagnostic fields
+//!
+//! - `code`: Code standard rule which was violated, e.g. `"III:B"`, or `null` if there is none.
+//! - `severity`: One of `"bug"`, `"error"`, `"warning"`, `"note"`, or `"help"`.
+//! - `message`: Main message describing the problem.
+//! - `file`: Name of the file containing the primary label, or `null` if there is no primary
+//!   label.
+//! - `labels`: List of labels, in the order they were produced. See below.
+//! - `notes`: List of strings containing additional information.
+//! - `suggestions`: List of suggested changes to the code. See below.
+//!
+//! ## Label fields
+//!
+//! - `style`: Either `"primary"` (the location of the problem) or `"secondary"` (related
+//!   locations which provide context).
+//! - `file`: Name of the file the label refers to.
+//! - `message`: Message attached to the label. May be empty.
+//! - `byte_range`: Byte offsets of the start (inclusive) and end (exclusive) of the labeled range
+//!   within the file.
+//! - `start`/`end`: Line and column numbers of the start and end of the labeled range. Both
+//!   lines and columns are 1-based. Columns are counted in Unicode characters, not bytes.
+//!
+//! ## Suggestion fields
+//!
+//! - `message`: Message describing the suggested change.
+//! - `replacement`: Text with which to replace the range. May be empty to delete the range.
+//! - `file`, `byte_range`, `start`, `end`: Location of the range of code to replace, as for
+//!   labels. The range may be empty to insert text.
+
+use std::{fmt::Display, io::Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, LabelStyle},
+    files::Files,
+};
+use serde::Serialize;
+
+use crate::rules::api::Suggestion;
+
+use super::severity_name;
+
+/// Version of the JSON schema produced by this module.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Top-level JSON object.
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    version: u32,
+    diagnostics: Vec<JsonDiagnostic<'a>>,
+}
+
+/// JSON representation of a [`Diagnostic`].
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    code: Option<&'a str>,
+    severity: &'static str,
+    message: &'a str,
+    file: Option<String>,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+    suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+/// JSON representation of a [`Label`].
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    style: &'static str,
+    file: String,
+    message: &'a str,
+    byte_range: JsonByteRange,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// JSON representation of a [`Suggestion`].
+#[derive(Serialize)]
+struct JsonSuggestion<'a> {
+    message: &'a str,
+    replacement: &'a str,
+    file: String,
+    byte_range: JsonByteRange,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+/// Range of bytes within a file.
+#[derive(Serialize)]
+struct JsonByteRange {
+    start: usize,
+    end: usize,
+}
+
+/// Line/column position within a file.
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+/// Writes the given diagnostics to `writer` as a JSON document.
+///
+/// `suggestions[i]` gives the suggestions for `diagnostics[i]`. Any diagnostics past the end of
+/// `suggestions` are written without suggestions. See the [module-level documentation][self] for
+/// a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any label of the given diagnostics refers to a file or location which is not in the
+/// given [`Files`] database.
+pub fn write_json<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+    suggestions: &[Vec<Suggestion>],
+) -> std::io::Result<()>
+where
+    F: Files<'files, Name: Display>,
+    W: Write,
+{
+    let output = JsonOutput {
+        version: SCHEMA_VERSION,
+        diagnostics: diagnostics
+            .iter()
+            .enumerate()
+            .map(|(i, diagnostic)| {
+                let suggestions = suggestions.get(i).map_or(&[][..], Vec::as_slice);
+                json_diagnostic(files, diagnostic, suggestions)
+            })
+            .collect(),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &output)?;
+    writeln!(writer)
+}
+
+/// Converts a [`Diagnostic`] into its JSON representation.
+fn json_diagnostic<'a, 'files, F>(
+    files: &'files F,
+    diagnostic: &'a Diagnostic<F::FileId>,
+    suggestions: &'a [Suggestion],
+) -> JsonDiagnostic<'a>
+where
+    F: Files<'files, Name: Display>,
+{
+    let primary_file_id = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .map(|label| label.file_id);
+    let file = primary_file_id.map(|file_id| file_name(files, file_id));
+    JsonDiagnostic {
+        code: diagnostic.code.as_deref(),
+        severity: severity_name(diagnostic.severity),
+        message: &diagnostic.message,
+        file,
+        labels: diagnostic.labels.iter().map(|label| json_label(files, label)).collect(),
+        notes: &diagnostic.notes,
+        // Suggestions apply to the file containing the primary label
+        suggestions: primary_file_id.map_or_else(Vec::new, |file_id| {
+            suggestions
+                .iter()
+                .map(|suggestion| json_suggestion(files, file_id, suggestion))
+                .collect()
+        }),
+    }
+}
+
+/// Converts a [`Label`] into its JSON representation.
+fn json_label<'a, 'files, F>(files: &'files F, label: &'a Label<F::FileId>) -> JsonLabel<'a>
+where
+    F: Files<'files, Name: Display>,
+{
+    let position = |byte_index| json_position(files, label.file_id, byte_index);
+    JsonLabel {
+        style: match label.style {
+            LabelStyle::Primary => "primary",
+            LabelStyle::Secondary => "secondary",
+        },
+        file: file_name(files, label.file_id),
+        message: &label.message,
+        byte_range: JsonByteRange {
+            start: label.range.start,
+            end: label.range.end,
+        },
+        start: position(label.range.start),
+        end: position(label.range.end),
+    }
+}
+
+/// Converts a [`Suggestion`] for the file with the given ID into its JSON representation.
+fn json_suggestion<'a, 'files, F>(
+    files: &'files F,
+    file_id: F::FileId,
+    suggestion: &'a Suggestion,
+) -> JsonSuggestion<'a>
+where
+    F: Files<'files, Name: Display>,
+{
+    let range = &suggestion.edit.range;
+    JsonSuggestion {
+        message: &suggestion.message,
+        replacement: &suggestion.edit.replacement,
+        file: file_name(files, file_id),
+        byte_range: JsonByteRange {
+            start: range.start,
+            end: range.end,
+        },
+        start: json_position(files, file_id, range.start),
+        end: json_position(files, file_id, range.end),
+    }
+}
+
+/// Returns the line/column position of a byte offset within a file.
+fn json_position<'files, F>(files: &'files F, file_id: F::FileId, byte_index: usize) -> JsonPosition
+where
+    F: Files<'files, Name: Display>,
+{
+    let location = files
+        .location(file_id, byte_index)
+        .expect("Expected label to refer to a valid location");
+    JsonPosition {
+        line: location.line_number,
+        column: location.column_number,
+    }
+}
+
+/// Returns the name of the file with the given ID.
+fn file_name<'files, F>(files: &'files F, file_id: F::FileId) -> String
+where
+    F: Files<'files, Name: Display>,
+{
+    files
+        .name(file_id)
+        .expect("Expected to find a file with the given ID")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+
+    use crate::rules::api::{Edit, Suggestion};
+
+    /// Tests that all parts of a diagnostic, including secondary labels, notes, and suggestions,
+    /// are present in the output.
+    #[test]
+    fn write_json() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int main() {\n  int Foo;\n}\n");
+        let diagnostics = [Diagnostic::warning()
+            .with_code("I:A")
+            .with_message("Variable names must be in lower snake case.")
+            .with_label(Label::primary(file_id, 19..22).with_message("Name contains uppercase"))
+            .with_label(Label::secondary(file_id, 0..10))
+            .with_note("A note")];
+
+        let mut output: Vec<u8> = Vec::new();
+        let suggestions = [vec![Suggestion {
+            message: "Rename the variable".to_owned(),
+            edit: Edit::replace(19..22, "foo"),
+        }]];
+        super::write_json(&mut output, &files, &diagnostics, &suggestions).unwrap();
+        let actual: Value = serde_json::from_slice(&output).unwrap();
+        let expected = json!({
+            "version": 1,
+            "diagnostics": [{
+                "code": "I:A",
+                "severity": "warning",
+                "message": "Variable names must be in lower snake case.",
+                "file": "test.c",
+                "labels": [
+                    {
+                        "style": "primary",
+                        "file": "test.c",
+                        "message": "Name contains uppercase",
+                        "byte_range": { "start": 19, "end": 22 },
+                        "start": { "line": 2, "column": 7 },
+                        "end": { "line": 2, "column": 10 },
+                    },
+                    {
+                        "style": "secondary",
+                        "file": "test.c",
+                        "message": "",
+                        "byte_range": { "start": 0, "end": 10 },
+                        "start": { "line": 1, "column": 1 },
+                        "end": { "line": 1, "column": 11 },
+                    },
+                ],
+                "notes": ["A note"],
+                "suggestions": [{
+                    "message": "Rename the variable",
+                    "replacement": "foo",
+                    "file": "test.c",
+                    "byte_range": { "start": 19, "end": 22 },
+                    "start": { "line": 2, "column": 7 },
+                    "end": { "line": 2, "column": 10 },
+                }],
+            }],
+        });
+        assert_eq!(expected, actual);
+    }
+}
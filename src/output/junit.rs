@@ -0,0 +1,331 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JUnit XML output format, for test report dashboards.
+//!
+//! Each checked file becomes a `<testsuite>`, containing a `<testcase>` for each [rule][Rule]
+//! which was checked. A test case passes if its rule found no problems. Otherwise it contains a
+//! `<failure>` element for each diagnostic, whose text gives the location, message, and notes.
+//! Rules which can't be checked programmatically are marked as `<skipped/>`:
+//!
+//! ```xml
+//! <?xml version="1.0" encoding="UTF-8"?>
+//! <testsuites name="Westwood" tests="16" failures="1" skipped="1">
+//!   <testsuite name="hw5.c" tests="16" failures="1" skipped="1">
+//!     <testcase name="I:B: Use descriptive and meaningful names" classname="hw5.c">
+//!       <skipped message="This rule can't be checked automatically"/>
+//!     </testcase>
+//!     <testcase name="III:B: Put one space on each side of binary operators" classname="hw5.c">
+//!       <failure type="warning" message="Expected a single space on each side of binary operator">hw5.c:12:8: Expected a single space on each side of binary operator</failure>
+//!     </testcase>
+//!     ...
+//!   </testsuite>
+//! </testsuites>
+//! ```
+//!
+//! Diagnostics with a severity of note or help are not failures, so they are included in the test
+//! case's `<system-out>` element instead. Diagnostics which do not come from a rule, such as
+//! syntax errors, are reported in an extra test case named `Other diagnostics`, which is only
+//! present when there are such diagnostics.
+
+use std::io::{self, Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, LabelStyle, Severity},
+    files::Files,
+};
+
+use crate::rules::api::Rule;
+
+use super::{escape_xml, rule_code};
+
+/// Name of the test case containing diagnostics which do not come from a rule.
+const OTHER_TEST_CASE: &str = "Other diagnostics";
+
+/// Message given for test cases of rules which can't be checked.
+const SKIPPED_MESSAGE: &str = "This rule can't be checked automatically";
+
+/// Writes a JUnit XML report to `writer`.
+///
+/// `checked_files` gives the ID of each file which was checked, along with the rules it was
+/// checked with. Each diagnostic is reported in the test suite of the file containing its primary
+/// label. See the [module-level documentation][self] for details.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any of the given file IDs, or any label of the given diagnostics, refers to a file or
+/// location which is not in the given [`Files`] database.
+pub fn write_junit<'files, 'rules, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+    checked_files: impl IntoIterator<Item = (F::FileId, &'rules [Box<dyn Rule>])>,
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>, FileId: PartialEq>,
+    W: Write,
+{
+    let suites: Vec<TestSuite> = checked_files
+        .into_iter()
+        .map(|(file_id, rules)| TestSuite::new(files, file_id, rules, diagnostics))
+        .collect();
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="Westwood" tests="{}" failures="{}" skipped="{}">"#,
+        suites.iter().map(|suite| suite.cases.len()).sum::<usize>(),
+        suites.iter().map(TestSuite::failures).sum::<usize>(),
+        suites.iter().map(TestSuite::skipped).sum::<usize>(),
+    )?;
+    for suite in &suites {
+        suite.write(writer)?;
+    }
+    writeln!(writer, "</testsuites>")
+}
+
+/// Test suite for one file.
+struct TestSuite {
+    name: String,
+    cases: Vec<TestCase>,
+}
+
+/// Test case for one rule.
+struct TestCase {
+    name: String,
+    /// Whether the test case's rule can't be checked. See [`Rule::is_checked()`].
+    skipped: bool,
+    /// Message, type, and full text of each diagnostic which is a failure.
+    failures: Vec<(String, &'static str, String)>,
+    /// Full text of diagnostics which are not failures.
+    output: Vec<String>,
+}
+
+impl TestSuite {
+    fn new<'files, F>(
+        files: &'files F,
+        file_id: F::FileId,
+        rules: &[Box<dyn Rule>],
+        diagnostics: &[Diagnostic<F::FileId>],
+    ) -> Self
+    where
+        F: Files<'files, Name: AsRef<str>, FileId: PartialEq>,
+    {
+        let name = files
+            .name(file_id)
+            .expect("Expected to find a file with the given ID")
+            .as_ref()
+            .to_owned();
+        let mut cases: Vec<TestCase> = rules
+            .iter()
+            .map(|rule| {
+                let mut case = TestCase::new(format!("{}: {}", rule.code(), rule.title()));
+                case.skipped = !rule.is_checked();
+                case
+            })
+            .collect();
+        let mut other = TestCase::new(OTHER_TEST_CASE.to_owned());
+
+        for diagnostic in diagnostics {
+            let Some(label) =
+                diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary)
+            else {
+                continue;
+            };
+            if label.file_id != file_id {
+                continue;
+            }
+            let location = files
+                .location(file_id, label.range.start)
+                .expect("Expected label to refer to a valid location");
+            let mut text = format!(
+                "{name}:{}:{}: {}",
+                location.line_number, location.column_number, diagnostic.message
+            );
+            for note in &diagnostic.notes {
+                text.push('\n');
+                text.push_str(note);
+            }
+
+            let case = rule_code(diagnostic)
+                .and_then(|code| rules.iter().position(|rule| rule.code() == code))
+                .map_or(&mut other, |index| &mut cases[index]);
+            let kind = match diagnostic.severity {
+                Severity::Bug => "bug",
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note | Severity::Help => {
+                    case.output.push(text);
+                    continue;
+                }
+            };
+            case.failures.push((diagnostic.message.clone(), kind, text));
+        }
+
+        if !other.failures.is_empty() || !other.output.is_empty() {
+            cases.push(other);
+        }
+        Self { name, cases }
+    }
+
+    /// Returns the number of failed test cases.
+    fn failures(&self) -> usize {
+        self.cases.iter().filter(|case| !case.failures.is_empty()).count()
+    }
+
+    /// Returns the number of skipped test cases.
+    fn skipped(&self) -> usize {
+        self.cases.iter().filter(|case| case.skipped).count()
+    }
+
+    fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let name = escape_xml(&self.name);
+        writeln!(
+            writer,
+            r#"  <testsuite name="{name}" tests="{}" failures="{}" skipped="{}">"#,
+            self.cases.len(),
+            self.failures(),
+            self.skipped()
+        )?;
+        for case in &self.cases {
+            write!(
+                writer,
+                r#"    <testcase name="{}" classname="{name}""#,
+                escape_xml(&case.name)
+            )?;
+            if !case.skipped && case.failures.is_empty() && case.output.is_empty() {
+                writeln!(writer, "/>")?;
+                continue;
+            }
+            writeln!(writer, ">")?;
+            if case.skipped {
+                writeln!(writer, r#"      <skipped message="{SKIPPED_MESSAGE}"/>"#)?;
+            }
+            for (message, kind, text) in &case.failures {
+                writeln!(
+                    writer,
+                    r#"      <failure type="{kind}" message="{}">{}</failure>"#,
+                    escape_xml(message),
+                    escape_xml(text)
+                )?;
+            }
+            if !case.output.is_empty() {
+                writeln!(
+                    writer,
+                    "      <system-out>{}</system-out>",
+                    escape_xml(&case.output.join("\n"))
+                )?;
+            }
+            writeln!(writer, "    </testcase>")?;
+        }
+        writeln!(writer, "  </testsuite>")
+    }
+}
+
+impl TestCase {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            skipped: false,
+            failures: Vec::new(),
+            output: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::{config::Config, rules::get_rules};
+
+    #[test]
+    fn write_junit() {
+        let mut files = SimpleFiles::new();
+        let file_a = files.add("a.c", "int main() {\n  return 1+2;\n}\n");
+        let file_b = files.add("b.c", "int main() {\n  return 0;\n}\n");
+        let diagnostics = [
+            Diagnostic::warning()
+                .with_code("III:B")
+                .with_message("Expected a single space on each side of binary operator")
+                .with_label(Label::primary(file_a, 23..24)),
+            Diagnostic::note()
+                .with_code("III:B")
+                .with_message("Not a failure")
+                .with_label(Label::primary(file_a, 23..24)),
+            Diagnostic::error()
+                .with_message("Syntax error: unexpected `\x01'")
+                .with_label(Label::primary(file_a, 1..2)),
+            Diagnostic::error()
+                .with_message("Syntax error: expected `<'")
+                .with_label(Label::primary(file_a, 0..1)),
+        ];
+        let rules = get_rules(&Config::default());
+
+        let mut output: Vec<u8> = Vec::new();
+        super::write_junit(
+            &mut output,
+            &files,
+            &diagnostics,
+            [(file_a, rules.as_slice()), (file_b, rules.as_slice())],
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        let n = rules.len();
+        assert_eq!(
+            format!(
+                r#"<testsuites name="Westwood" tests="{}" failures="2" skipped="2">"#,
+                2 * n + 1
+            ),
+            lines[1]
+        );
+        assert_eq!(
+            format!(r#"  <testsuite name="a.c" tests="{}" failures="2" skipped="1">"#, n + 1),
+            lines[2]
+        );
+        assert!(output.contains(concat!(
+            r#"    <testcase name="III:B: Put one space on each side of binary operators" classname="a.c">"#,
+            "\n",
+            r#"      <failure type="warning" message="Expected a single space on each side of binary operator">a.c:2:11: Expected a single space on each side of binary operator</failure>"#,
+            "\n",
+            "      <system-out>a.c:2:11: Not a failure</system-out>\n",
+            "    </testcase>\n",
+        )));
+        assert!(output.contains(
+            r#"      <failure type="error" message="Syntax error: expected `&lt;&apos;">a.c:1:1: Syntax error: expected `&lt;&apos;</failure>"#
+        ));
+        assert!(output.contains("a.c:1:2: Syntax error: unexpected `\u{fffd}&apos;</failure>"));
+        assert!(output.contains(concat!(
+            r#"    <testcase name="I:B: Use descriptive and meaningful names" classname="a.c">"#,
+            "\n",
+            r#"      <skipped message="This rule can't be checked automatically"/>"#,
+            "\n",
+            "    </testcase>\n",
+        )));
+        assert!(output.contains(r#"    <testcase name="I:A: Variable names must be lowercase, with words separated by underscores" classname="a.c"/>"#));
+        assert!(output.contains(&format!(
+            r#"  <testsuite name="b.c" tests="{n}" failures="0" skipped="1">"#
+        )));
+    }
+}
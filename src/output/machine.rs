@@ -0,0 +1,231 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Machine-parseable output format.
+//!
+//! # Format
+//!
+//! Each diagnostic is printed as a series of records, one per line. The first record holds the
+//! severity, code, and message of the diagnostic, and the second holds the location of its first
+//! primary label. These are followed by one indented continuation record for each note, then one
+//! for each other label, and then one for each [suggestion][Suggestion]. An example should suffice
+//! to describe the format:
+//!
+//! ```text
+//! WARNING: [I:D] All top-level declarations must come before function definitions
+//!          at hw8_main.c from line 212 column 1 to line 217 column 2: Declaration occurs here
+//!          note: Move this declaration above the first function
+//!          label at hw8_main.c from line 20 column 1 to line 24 column 2: First function defined here
+//! WARNING: [III:B] Expected a single space on each side of binary operator
+//!          at hw5.c from line 12 column 8 to line 12 column 11
+//!          suggestion at hw5.c from line 12 column 9 to line 12 column 10: " + ": Put one space on each side of the operator
+//! ```
+//!
+//! Continuation records start with whitespace. Notes containing multiple lines are split into
+//! multiple `note:` records. The `: message` part of a location or label record is omitted if the
+//! label has no message.
+//!
+//! A suggestion record gives the range of code to replace, followed by the replacement text as a
+//! JSON string, and then the suggestion's message.
+//!
+//! # Vim integration
+//!
+//! The following [`'errorformat'`][efm] parses this format. Each diagnostic becomes a quickfix
+//! entry whose text includes its notes and the message of its primary label, and each other label
+//! and suggestion becomes a separate entry of type "info" following it.
+//!
+//! ```vim
+//! set errorformat=%EERROR:\ %m,%EBUG:\ %m,%WWARNING:\ %m,%INOTE:\ %m,%IHELP:\ %m,
+//!     \%C%\\s%#at\ %f\ from\ line\ %l\ column\ %c\ to\ line\ %e\ column\ %k:\ %m,
+//!     \%C%\\s%#at\ %f\ from\ line\ %l\ column\ %c\ to\ line\ %e\ column\ %k,
+//!     \%C%\\s%#note:\ %m,
+//!     \%I%\\s%#label\ at\ %f\ from\ line\ %l\ column\ %c\ to\ line\ %e\ column\ %k:\ %m,
+//!     \%I%\\s%#label\ at\ %f\ from\ line\ %l\ column\ %c\ to\ line\ %e\ column\ %k,
+//!     \%I%\\s%#suggestion\ at\ %f\ from\ line\ %l\ column\ %c\ to\ line\ %e\ column\ %k:\ %m
+//! set makeprg=westwood\ --format\ machine\ %
+//! ```
+//!
+//! [efm]: https://vimhelp.org/quickfix.txt.html#errorformat
+
+use std::io::{self, Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, LabelStyle, Severity},
+    files::Files,
+};
+
+use crate::rules::api::Suggestion;
+
+/// Writes a [`Diagnostic`] and its suggestions to `writer` in a machine-parseable format.
+///
+/// See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// This function requires that the given diagnostic has at least one [`Label`] with a style
+/// of [`Primary`][2]. If this is not the case, it will panic.
+///
+/// This function also panics if any label of the given diagnostic has a file ID which is not in
+/// the given [`Files`] database.
+///
+/// [2]: codespan_reporting::diagnostic::LabelStyle::Primary
+pub fn write_machine_parseable<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+    suggestions: &[Suggestion],
+) -> io::Result<()>
+where
+    F: Files<'files, Name: AsRef<str>>,
+    W: Write,
+{
+    let primary_label: &Label<_> = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .expect("Diagnostic has no primary label");
+    let severity = match diagnostic.severity {
+        Severity::Bug => "BUG",
+        Severity::Error => "ERROR",
+        Severity::Warning => "WARNING",
+        Severity::Note => "NOTE",
+        Severity::Help => "HELP",
+    };
+    let indent = severity.len() + 2;
+
+    write!(writer, "{severity}: ")?;
+    if let Some(code) = diagnostic.code.as_ref() {
+        write!(writer, "[{code}] ")?;
+    }
+    writeln!(writer, "{}", diagnostic.message)?;
+    write!(writer, "{:indent$}at {}", "", location(files, primary_label))?;
+    write_label_message(writer, primary_label)?;
+    for line in diagnostic.notes.iter().flat_map(|note| note.lines()) {
+        writeln!(writer, "{:indent$}note: {line}", "")?;
+    }
+    for label in &diagnostic.labels {
+        if std::ptr::eq(label, primary_label) {
+            continue;
+        }
+        write!(writer, "{:indent$}label at {}", "", location(files, label))?;
+        write_label_message(writer, label)?;
+    }
+    for suggestion in suggestions {
+        let label = Label::secondary(primary_label.file_id, suggestion.edit.range.clone());
+        writeln!(
+            writer,
+            "{:indent$}suggestion at {}: {}: {}",
+            "",
+            location(files, &label),
+            serde_json::Value::from(suggestion.edit.replacement.as_str()),
+            suggestion.message.replace('\n', " ")
+        )?;
+    }
+    Ok(())
+}
+
+/// Ends a location or label record with the message of the given label, if it has one.
+fn write_label_message<FileId>(writer: &mut impl Write, label: &Label<FileId>) -> io::Result<()> {
+    if label.message.is_empty() {
+        writeln!(writer)
+    } else {
+        writeln!(writer, ": {}", label.message.replace('\n', " "))
+    }
+}
+
+/// Formats the location of a label, e.g. `main.c from line 1 column 1 to line 2 column 5`.
+fn location<'files, F>(files: &'files F, label: &Label<F::FileId>) -> String
+where
+    F: Files<'files, Name: AsRef<str>>,
+{
+    let filename = files.name(label.file_id).expect("Expected to find a file with the given ID");
+    let start = files.location(label.file_id, label.range.start).unwrap();
+    let end = files.location(label.file_id, label.range.end).unwrap();
+    format!(
+        "{} from line {} column {} to line {} column {}",
+        filename.as_ref(),
+        start.line_number,
+        start.column_number,
+        end.line_number,
+        end.column_number,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Edit, Suggestion};
+
+    /// Tests that notes and labels other than the first primary one are written as continuation
+    /// records.
+    #[test]
+    fn write_machine_parseable() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int x;\nint main() {\n}\nint y;\nint z;\n");
+        let diagnostic = Diagnostic::warning()
+            .with_code("I:D")
+            .with_message("All top-level declarations must come before function definitions")
+            .with_label(Label::primary(file_id, 22..28).with_message("Declaration occurs here"))
+            .with_label(
+                Label::secondary(file_id, 7..21).with_message("First function defined here"),
+            )
+            .with_label(Label::secondary(file_id, 0..6))
+            .with_label(Label::primary(file_id, 29..35).with_message("And here"))
+            .with_note("First line\nSecond line");
+        let expected = indoc! {"
+            WARNING: [I:D] All top-level declarations must come before function definitions
+                     at test.c from line 4 column 1 to line 4 column 7: Declaration occurs here
+                     note: First line
+                     note: Second line
+                     label at test.c from line 2 column 1 to line 3 column 2: First function defined here
+                     label at test.c from line 1 column 1 to line 1 column 7
+                     label at test.c from line 5 column 1 to line 5 column 7: And here
+        "};
+        let mut output: Vec<u8> = Vec::new();
+        super::write_machine_parseable(&mut output, &files, &diagnostic, &[]).unwrap();
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn suggestions() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int main() {\n  return 1+2;\n}\n");
+        let diagnostic = Diagnostic::warning()
+            .with_code("III:B")
+            .with_message("Expected a single space on each side of binary operator")
+            .with_label(Label::primary(file_id, 22..25));
+        let suggestions = [Suggestion {
+            message: "Put one space on each side of the operator".to_owned(),
+            edit: Edit::replace(23..24, " + "),
+        }];
+        let expected = indoc! {r#"
+            WARNING: [III:B] Expected a single space on each side of binary operator
+                     at test.c from line 2 column 10 to line 2 column 13
+                     suggestion at test.c from line 2 column 11 to line 2 column 12: " + ": Put one space on each side of the operator
+        "#};
+        let mut output: Vec<u8> = Vec::new();
+        super::write_machine_parseable(&mut output, &files, &diagnostic, &suggestions).unwrap();
+        assert_eq!(expected, String::from_utf8(output).unwrap());
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Formats in which diagnostics can be printed.
+
+use codespan_reporting::diagnostic::{Diagnostic, Severity};
+
+use crate::rules::api::RuleCode;
+
+pub mod annotated;
+pub mod checkstyle;
+pub mod codeclimate;
+pub mod gcc;
+pub mod github;
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod machine;
+pub mod pretty;
+pub mod sarif;
+
+/// Escapes text for use in XML (or HTML) content or attribute values.
+///
+/// Characters which are not allowed in XML documents at all, such as most ASCII control
+/// characters, are replaced with U+FFFD REPLACEMENT CHARACTER.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' => escaped.push_str("&#9;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\0'..='\x1f' | '\u{fffe}' | '\u{ffff}' => escaped.push(char::REPLACEMENT_CHARACTER),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the code of the rule which produced the given diagnostic, if it has a valid code.
+fn rule_code<FileId>(diagnostic: &Diagnostic<FileId>) -> Option<RuleCode> {
+    diagnostic.code.as_deref().and_then(|code| code.parse().ok())
+}
+
+/// Returns the name of a severity level, as used in configuration files.
+#[must_use]
+pub fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug => "bug",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+        Severity::Help => "help",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn escape_xml() {
+        assert_eq!(
+            "&lt;a href=&quot;&amp;&apos;&quot;&gt;&#9;x&#13;&#10;\u{fffd}\u{fffd}\u{fffd}é",
+            super::escape_xml("<a href=\"&'\">\tx\r\n\0\x08\x1bé")
+        );
+    }
+}
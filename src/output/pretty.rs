@@ -0,0 +1,155 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pretty human-readable output format.
+//!
+//! Diagnostics are rendered by [`codespan_reporting`]. Each [suggestion][Suggestion] for a
+//! diagnostic is shown directly below it as a `help:` message, along with the line(s) of code as
+//! they would be after applying the suggestion, similarly to `rustc`:
+//!
+//! ```text
+//! warning[III:B]: Expected a single space on each side of binary operator
+//!    ┌─ hw5.c:12:8
+//!    │
+//! 12 │   return 1+2;
+//!    │           ^
+//! help: Put one space on each side of the operator
+//!    ┌─ hw5.c:12:8
+//!    │
+//! 12 │   return 1 + 2;
+//!    │           ---
+//! ```
+
+use std::io::Write;
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, LabelStyle},
+    files::{self, Files, SimpleFile},
+    term::{
+        self,
+        termcolor::{Buffer, WriteColor},
+    },
+};
+
+use crate::{fix::apply_edits, rules::api::Suggestion};
+
+/// Writes a [`Diagnostic`] and its suggestions to `writer`.
+///
+/// See the [module-level documentation][self] for a description of the format.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails or if any label of the given diagnostic refers to
+/// a file or location which is not in the given [`Files`] database.
+pub fn write_pretty<'files, F, W>(
+    writer: &mut W,
+    config: &term::Config,
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+    suggestions: &[Suggestion],
+) -> Result<(), files::Error>
+where
+    F: Files<'files>,
+    W: WriteColor,
+{
+    // Suggestions apply to the file containing the primary label
+    let file_id = diagnostic
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .map(|label| label.file_id);
+    let (Some(file_id), false) = (file_id, suggestions.is_empty()) else {
+        return term::emit(writer, config, files, diagnostic);
+    };
+
+    // Each diagnostic is followed by a blank line, which we remove so that the suggestions are
+    // shown as part of the diagnostic.
+    let mut buffer = if writer.supports_color() {
+        Buffer::ansi()
+    } else {
+        Buffer::no_color()
+    };
+    term::emit(&mut buffer, config, files, diagnostic)?;
+    let name = files.name(file_id)?.to_string();
+    let source = files.source(file_id)?;
+    for suggestion in suggestions {
+        let edit = &suggestion.edit;
+        let fixed = SimpleFile::new(&name, apply_edits(source.as_ref(), [edit]));
+        let help =
+            Diagnostic::help()
+                .with_message(&suggestion.message)
+                .with_label(Label::secondary(
+                    (),
+                    edit.range.start..(edit.range.start + edit.replacement.len()),
+                ));
+        strip_blank_line(&mut buffer);
+        term::emit(&mut buffer, config, &fixed, &help)?;
+    }
+    writer.write_all(buffer.as_slice())?;
+    Ok(())
+}
+
+/// Removes the trailing blank line from a buffer containing a rendered diagnostic.
+fn strip_blank_line(buffer: &mut Buffer) {
+    let contents = buffer.as_slice();
+    if contents.ends_with(b"\n\n") {
+        let contents = contents[..(contents.len() - 1)].to_vec();
+        buffer.clear();
+        buffer.write_all(&contents).expect("Writing to a buffer cannot fail");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+        term::{self, termcolor::NoColor},
+    };
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Edit, Suggestion};
+
+    #[test]
+    fn write_pretty() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("test.c", "int main() {\n  return 1+2;\n}\n");
+        let diagnostic = Diagnostic::warning()
+            .with_code("III:B")
+            .with_message("Expected a single space on each side of binary operator")
+            .with_label(Label::primary(file_id, 22..25));
+        let suggestions = [Suggestion {
+            message: "Put one space on each side of the operator".to_owned(),
+            edit: Edit::replace(23..24, " + "),
+        }];
+        let expected = indoc! {"
+            warning[III:B]: Expected a single space on each side of binary operator
+              ┌─ test.c:2:10
+              │
+            2 │   return 1+2;
+              │          ^^^
+            help: Put one space on each side of the operator
+              ┌─ test.c:2:11
+              │
+            2 │   return 1 + 2;
+              │           ---
+
+        "};
+        let mut output = NoColor::new(Vec::new());
+        let config = term::Config::default();
+        super::write_pretty(&mut output, &config, &files, &diagnostic, &suggestions).unwrap();
+        assert_eq!(expected, String::from_utf8(output.into_inner()).unwrap());
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [SARIF 2.1.0][sarif] output format.
+//!
+//! The output contains a single run. Each [rule][Rule] is listed in the run's `tool.driver.rules`
+//! array, using its code (e.g. `III:B`) as its ID, its title as its short description, and the
+//! text of the code standard as its full description. Each [`Diagnostic`] becomes a result:
+//! - primary labels become the result's `locations`;
+//! - secondary labels become the result's `relatedLocations`;
+//! - notes are appended to the result's message, each on its own line.
+//!
+//! Columns are counted in Unicode code points, as indicated by the run's `columnKind` property.
+//!
+//! [sarif]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::{fmt::Display, io::Write};
+
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, LabelStyle, Severity},
+    files::Files,
+};
+use serde_json::{json, Value};
+
+use crate::rules::api::Rule;
+
+use super::rule_code;
+
+/// URI of the SARIF 2.1.0 JSON schema.
+const SCHEMA_URI: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Writes the given diagnostics to `writer` as a SARIF log.
+///
+/// See the [module-level documentation][self] for details.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+///
+/// # Panics
+///
+/// Panics if any label of the given diagnostics refers to a file or location which is not in the
+/// given [`Files`] database.
+pub fn write_sarif<'files, F, W>(
+    writer: &mut W,
+    files: &'files F,
+    diagnostics: &[Diagnostic<F::FileId>],
+    rules: &[Box<dyn Rule>],
+) -> std::io::Result<()>
+where
+    F: Files<'files, Name: Display>,
+    W: Write,
+{
+    let rule_descriptors: Vec<Value> = rules.iter().map(|rule| rule_descriptor(&**rule)).collect();
+    let results: Vec<Value> = diagnostics
+        .iter()
+        .map(|diagnostic| sarif_result(files, diagnostic, rules))
+        .collect();
+    let log = json!({
+        "$schema": SCHEMA_URI,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "Westwood",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rule_descriptors,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    });
+    serde_json::to_writer_pretty(&mut *writer, &log)?;
+    writeln!(writer)
+}
+
+/// Converts a [`Rule`] into a SARIF `reportingDescriptor` object.
+fn rule_descriptor(rule: &dyn Rule) -> Value {
+    let metadata = rule.metadata();
+    json!({
+        "id": metadata.code.to_string(),
+        "shortDescription": { "text": metadata.title },
+        "fullDescription": { "text": metadata.standard },
+        "defaultConfiguration": { "level": sarif_level(metadata.default_severity) },
+    })
+}
+
+/// Returns the SARIF `level` corresponding to a [`Severity`].
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "note",
+    }
+}
+
+/// Converts a [`Diagnostic`] into a SARIF `result` object.
+fn sarif_result<'files, F>(
+    files: &'files F,
+    diagnostic: &Diagnostic<F::FileId>,
+    rules: &[Box<dyn Rule>],
+) -> Value
+where
+    F: Files<'files, Name: Display>,
+{
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    let labels_with_style =
+        |style: LabelStyle| diagnostic.labels.iter().filter(move |label| label.style == style);
+    let locations: Vec<Value> = labels_with_style(LabelStyle::Primary)
+        .map(|label| sarif_location(files, label))
+        .collect();
+    let related_locations: Vec<Value> = labels_with_style(LabelStyle::Secondary)
+        .zip(1..)
+        .map(|(label, id)| {
+            let mut location = sarif_location(files, label);
+            location["id"] = json!(id);
+            location
+        })
+        .collect();
+
+    let mut result = json!({
+        "level": sarif_level(diagnostic.severity),
+        "message": { "text": message },
+        "locations": locations,
+    });
+    if let Some(code) = diagnostic.code.as_deref() {
+        result["ruleId"] = json!(code);
+        let index = rule_code(diagnostic)
+            .and_then(|code| rules.iter().position(|rule| rule.code() == code));
+        if let Some(index) = index {
+            result["ruleIndex"] = json!(index);
+        }
+    }
+    if !related_locations.is_empty() {
+        result["relatedLocations"] = json!(related_locations);
+    }
+    result
+}
+
+/// Converts a [`Label`] into a SARIF `location` object.
+fn sarif_location<'files, F>(files: &'files F, label: &Label<F::FileId>) -> Value
+where
+    F: Files<'files, Name: Display>,
+{
+    let name = files.name(label.file_id).expect("Expected to find a file with the given ID");
+    let start = files
+        .location(label.file_id, label.range.start)
+        .expect("Expected label to refer to a valid location");
+    let end = files
+        .location(label.file_id, label.range.end)
+        .expect("Expected label to refer to a valid location");
+    let mut location = json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": path_to_uri(&name.to_string()) },
+            "region": {
+                "startLine": start.line_number,
+                "startColumn": start.column_number,
+                "endLine": end.line_number,
+                "endColumn": end.column_number,
+                "byteOffset": label.range.start,
+                "byteLength": label.range.len(),
+            },
+        },
+    });
+    if !label.message.is_empty() {
+        location["message"] = json!({ "text": label.message });
+    }
+    location
+}
+
+/// Converts a file path into a URI reference by percent-encoding all characters other than
+/// unreserved characters and path separators. Absolute paths are given the `file://` scheme, and
+/// relative paths are left relative.
+fn path_to_uri(path: &str) -> String {
+    let mut uri = String::with_capacity(path.len());
+    if path.starts_with('/') {
+        uri.push_str("file://");
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(char::from(byte));
+            }
+            b'\\' => uri.push('/'),
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::{
+        diagnostic::{Diagnostic, Label},
+        files::SimpleFiles,
+    };
+    use pretty_assertions::assert_eq;
+    use serde_json::{json, Value};
+
+    use crate::{config::Config, rules::get_rules};
+
+    /// Tests that rules are listed and that primary and secondary labels become locations and
+    /// related locations, respectively.
+    #[test]
+    fn write_sarif() {
+        let mut files = SimpleFiles::new();
+        let file_id = files.add("my dir/test.c", "int main() {\n  int Foo;\n}\n");
+        let diagnostics = [Diagnostic::warning()
+            .with_code("I:A")
+            .with_message("Variable names must be in lower snake case.")
+            .with_label(Label::primary(file_id, 19..22).with_message("Name contains uppercase"))
+            .with_label(Label::secondary(file_id, 0..10))
+            .with_note("A note")];
+        let rules = get_rules(&Config::default());
+
+        let mut output: Vec<u8> = Vec::new();
+        super::write_sarif(&mut output, &files, &diagnostics, &rules).unwrap();
+        let log: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!("2.1.0", log["version"]);
+        let run = &log["runs"][0];
+        assert_eq!(rules.len(), run["tool"]["driver"]["rules"].as_array().unwrap().len());
+        let descriptor = &run["tool"]["driver"]["rules"][0];
+        assert_eq!("I:A", descriptor["id"]);
+        assert_eq!("warning", descriptor["defaultConfiguration"]["level"]);
+        assert!(descriptor["fullDescription"]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("A. Variable names should be in all lowercase."));
+
+        let result = &run["results"][0];
+        assert_eq!("I:A", result["ruleId"]);
+        assert_eq!(0, result["ruleIndex"]);
+        assert_eq!("warning", result["level"]);
+        assert_eq!(
+            "Variable names must be in lower snake case.\nA note",
+            result["message"]["text"]
+        );
+        assert_eq!(
+            json!([{
+                "physicalLocation": {
+                    "artifactLocation": { "uri": "my%20dir/test.c" },
+                    "region": {
+                        "startLine": 2,
+                        "startColumn": 7,
+                        "endLine": 2,
+                        "endColumn": 10,
+                        "byteOffset": 19,
+                        "byteLength": 3,
+                    },
+                },
+                "message": { "text": "Name contains uppercase" },
+            }]),
+            result["locations"]
+        );
+        assert_eq!(1, result["relatedLocations"].as_array().unwrap().len());
+        assert_eq!(1, result["relatedLocations"][0]["id"]);
+        assert_eq!(1, result["relatedLocations"][0]["physicalLocation"]["region"]["startLine"]);
+    }
+}
@@ -14,10 +14,12 @@
 
 //! API for [rules][Rule].
 
-use codespan_reporting::diagnostic::Diagnostic;
+use std::{fmt::Display, ops::Range, str::FromStr};
+
+use codespan_reporting::diagnostic::{Diagnostic, Severity};
 use tree_sitter::Tree;
 
-use crate::helpers::LinesWithPosition;
+use crate::{helpers::LinesWithPosition, rules::docs};
 
 pub struct SourceInfo<'src> {
     pub tree: Tree,
@@ -32,6 +34,11 @@ impl<'src> SourceInfo<'src> {
             .set_language(&tree_sitter_c::LANGUAGE.into())
             .expect("Failed to set language");
         let tree = parser.parse(code, None).expect("Failed to parse code");
+        Self::with_tree(code, tree)
+    }
+
+    /// Constructs a [`SourceInfo`] from code which has already been parsed into `tree`.
+    pub fn with_tree(code: &'src str, tree: Tree) -> Self {
         let lines = LinesWithPosition::from(code).collect();
         Self { tree, code, lines }
     }
@@ -39,13 +46,301 @@ impl<'src> SourceInfo<'src> {
 
 /// Represents a linter rule.
 pub trait Rule {
+    /// Returns the code of the code standard rule which this rule checks, e.g. `III:B`.
+    #[must_use]
+    fn code(&self) -> RuleCode;
+
+    /// Returns a short summary of what this rule requires, e.g. "Do not use goto".
+    #[must_use]
+    fn title(&self) -> &'static str;
+
+    /// Returns the severity of the diagnostics this rule produces, unless overridden by the
+    /// configuration.
+    #[must_use]
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Returns `true` if this rule attaches fixes to (some of) its lints. See
+    /// [`lint()`][Self::lint].
+    #[must_use]
+    fn is_fixable(&self) -> bool {
+        false
+    }
+
+    /// Returns `false` if this rule can't be checked programmatically, so [`check()`][Self::check]
+    /// never finds any problems. Such rules exist only for completeness.
+    #[must_use]
+    fn is_checked(&self) -> bool {
+        true
+    }
+
+    /// Returns information about this rule for display to users.
+    #[must_use]
+    fn metadata(&self) -> RuleMetadata {
+        let code = self.code();
+        let standard = docs::standard_text(code).unwrap_or_default();
+        RuleMetadata {
+            code,
+            title: self.title(),
+            examples: docs::examples(&standard),
+            standard,
+            notes: docs::implementation_notes(code),
+            default_severity: self.default_severity(),
+            fixable: self.is_fixable(),
+        }
+    }
+
     /// Checks a source file for compliance with this rule.
     ///
+    /// The diagnostics' codes don't need to be set, since
+    /// [`check_source()`][super::check_source] sets them to [`code()`][Self::code].
+    ///
     /// # Arguments
     ///
-    /// - `filename`: Name of the file being checked.
-    /// - `tree`: [`Tree`] representing the file.
-    /// - `code`: Text/code of the given file.
+    /// - `source`: The file being checked, with its text, lines, and syntax tree.
     #[must_use]
     fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>>;
+
+    /// Checks a source file for compliance with this rule, like [`check()`][Self::check], but
+    /// also returns a fix for each diagnostic which can be fixed mechanically.
+    ///
+    /// The default implementation attaches no fixes to the diagnostics returned by
+    /// [`check()`][Self::check]. Rules which can fix problems override this method.
+    #[must_use]
+    fn lint(&self, source: &SourceInfo) -> Vec<Lint> {
+        self.check(source).into_iter().map(Lint::from).collect()
+    }
+}
+
+/// Information about a [`Rule`], as shown by `--list-rules` and `--explain`.
+#[derive(Clone, Debug)]
+pub struct RuleMetadata {
+    pub code: RuleCode,
+    /// See [`Rule::title()`].
+    pub title: &'static str,
+    /// Text of the code standard which the rule checks. For rules in the [`WESTWOOD_SECTION`],
+    /// this is a description of the rule in the same style instead.
+    pub standard: String,
+    /// Examples given in the code standard's text.
+    pub examples: Vec<String>,
+    /// Notes about how the rule is implemented, if any.
+    pub notes: Option<String>,
+    /// See [`Rule::default_severity()`].
+    pub default_severity: Severity,
+    /// See [`Rule::is_fixable()`].
+    pub fixable: bool,
+}
+
+/// Code identifying a rule of the code standard, e.g. `III:B`, which is rule B of section III.
+///
+/// Checks which don't correspond to a rule quoted from the code standard have codes in the
+/// [`WESTWOOD_SECTION`], which is written as `W`, e.g. `W:A`.
+///
+/// Codes are ordered by section, then by rule letter. The Westwood section comes last.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleCode {
+    /// Section number, e.g. 3 for section III.
+    pub section: u8,
+    /// Rule letter within the section, e.g. `'B'`.
+    pub letter: char,
+}
+
+impl RuleCode {
+    /// Constructs a rule code from a section number and rule letter.
+    #[must_use]
+    pub const fn new(section: u8, letter: char) -> Self {
+        Self { section, letter }
+    }
+
+    /// Constructs the code of a Westwood-specific check, e.g. `W:A`.
+    #[must_use]
+    pub const fn westwood(letter: char) -> Self {
+        Self::new(WESTWOOD_SECTION, letter)
+    }
+}
+
+/// Section number of the checks which don't correspond to a rule quoted from the code standard.
+/// See [`RuleCode`].
+pub const WESTWOOD_SECTION: u8 = u8::MAX;
+
+impl Display for RuleCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", section_name(self.section), self.letter)
+    }
+}
+
+impl FromStr for RuleCode {
+    type Err = String;
+
+    /// Parses a rule code such as `III:B`. Case is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid rule code `{s}': expected a code like `III:B'");
+        let (section, letter) = s.split_once(':').ok_or_else(invalid)?;
+        let section = parse_section(section).ok_or_else(invalid)?;
+        let mut letters = letter.chars();
+        match (letters.next(), letters.next()) {
+            (Some(letter), None) if letter.is_ascii_alphabetic() => {
+                Ok(Self::new(section, letter.to_ascii_uppercase()))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Parses a section name as written by [`section_name()`], e.g. `III` or `W`. Case is ignored.
+///
+/// Returns [`None`] if `s` is neither `W` nor a valid Roman numeral between 1 and 254.
+#[must_use]
+pub fn parse_section(s: &str) -> Option<u8> {
+    let s = s.to_ascii_uppercase();
+    if s == "W" {
+        return Some(WESTWOOD_SECTION);
+    }
+    // Only canonical numerals are accepted, so comparing against each one also validates `s`.
+    (1..WESTWOOD_SECTION).find(|&n| to_roman(n) == s)
+}
+
+/// Returns the name of a section as used in rule codes: `W` for the [`WESTWOOD_SECTION`], or
+/// the section number as a Roman numeral otherwise.
+#[must_use]
+pub fn section_name(section: u8) -> String {
+    if section == WESTWOOD_SECTION {
+        "W".to_owned()
+    } else {
+        to_roman(section)
+    }
+}
+
+/// Converts a section number to a Roman numeral.
+#[must_use]
+pub fn to_roman(mut n: u8) -> String {
+    const NUMERALS: [(u8, &str); 9] = [
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            roman.push_str(numeral);
+            n -= value;
+        }
+    }
+    roman
+}
+
+/// A [`Diagnostic`] produced by a [`Rule`], along with the edits needed to fix it.
+#[derive(Clone, Debug)]
+pub struct Lint {
+    pub diagnostic: Diagnostic<()>,
+    /// Machine-applicable edits which fix the problem described by the diagnostic. Empty if the
+    /// problem cannot be fixed mechanically.
+    pub fix: Vec<Edit>,
+    /// Suggested changes which are shown to the user along with the diagnostic, but are not
+    /// applied by `--fix`.
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Lint {
+    /// Adds the given edits to this lint's fix.
+    #[must_use]
+    pub fn with_fix(mut self, edits: impl IntoIterator<Item = Edit>) -> Self {
+        self.fix.extend(edits);
+        self
+    }
+
+    /// Adds a suggestion to this lint.
+    #[must_use]
+    pub fn with_suggestion(mut self, message: impl Into<String>, edit: Edit) -> Self {
+        self.suggestions.push(Suggestion {
+            message: message.into(),
+            edit,
+        });
+        self
+    }
+}
+
+impl From<Diagnostic<()>> for Lint {
+    fn from(diagnostic: Diagnostic<()>) -> Self {
+        Self {
+            diagnostic,
+            fix: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+}
+
+/// A change to the code suggested by a [`Lint`], e.g. the corrected spacing around an operator.
+///
+/// The edit applies to the file containing the diagnostic's primary label.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Message describing the change, e.g. "Put one space on each side of the operator".
+    pub message: String,
+    pub edit: Edit,
+}
+
+/// An edit to a source file, which replaces the text in a byte range with new text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    /// Range of bytes to replace. May be empty to insert text.
+    pub range: Range<usize>,
+    /// Text with which to replace the range. May be empty to delete text.
+    pub replacement: String,
+}
+
+impl Edit {
+    /// Constructs an edit which deletes the given range.
+    #[must_use]
+    pub fn delete(range: Range<usize>) -> Self {
+        Self {
+            range,
+            replacement: String::new(),
+        }
+    }
+
+    /// Constructs an edit which replaces the given range with `replacement`.
+    #[must_use]
+    pub fn replace(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self {
+            range,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::RuleCode;
+
+    #[test]
+    fn rule_code() {
+        let tests = [
+            ("I:A", RuleCode::new(1, 'A')),
+            ("III:B", RuleCode::new(3, 'B')),
+            ("xii:a", RuleCode::new(12, 'A')),
+            ("XIV:C", RuleCode::new(14, 'C')),
+            ("w:b", RuleCode::westwood('B')),
+        ];
+        for (text, code) in tests {
+            assert_eq!(Ok(code), text.parse());
+            assert_eq!(text.to_ascii_uppercase(), code.to_string());
+        }
+        for text in [
+            "", "III", "IIII:A", "III:", "III:AB", "III:1", "3:A", "CCLV:A", "WW:A",
+        ] {
+            assert!(text.parse::<RuleCode>().is_err(), "{text}");
+        }
+        assert!(RuleCode::new(2, 'B') < RuleCode::new(3, 'A'));
+        assert!(RuleCode::new(12, 'A') < RuleCode::westwood('A'));
+    }
 }
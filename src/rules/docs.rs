@@ -0,0 +1,215 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Documentation for [rules][super::api::Rule] which is shown to users at runtime.
+//!
+//! Each rule module begins with a `//!` comment quoting the part of the code standard which the
+//! rule checks. Rather than duplicating that text, the build script extracts these comments from
+//! the module sources, and the parts needed are picked out of them at runtime.
+
+use super::api::RuleCode;
+
+/// Module-level documentation of each rule module, without the comment markers. Generated by the
+/// build script.
+mod module_docs {
+    include!(concat!(env!("OUT_DIR"), "/rule_docs.rs"));
+}
+
+/// Module-level documentation of each rule's module.
+const RULE_DOCS: [(RuleCode, &str); 21] = [
+    (RuleCode::new(1, 'A'), module_docs::RULE01A),
+    (RuleCode::new(1, 'B'), module_docs::RULE01B),
+    (RuleCode::new(1, 'C'), module_docs::RULE01C),
+    (RuleCode::new(1, 'D'), module_docs::RULE01D),
+    (RuleCode::new(2, 'A'), module_docs::RULE02A),
+    (RuleCode::new(2, 'B'), module_docs::RULE02B),
+    (RuleCode::new(3, 'A'), module_docs::RULE03A),
+    (RuleCode::new(3, 'B'), module_docs::RULE03B),
+    (RuleCode::new(3, 'C'), module_docs::RULE03C),
+    (RuleCode::new(3, 'D'), module_docs::RULE03D),
+    (RuleCode::new(3, 'E'), module_docs::RULE03E),
+    (RuleCode::new(3, 'F'), module_docs::RULE03F),
+    (RuleCode::new(11, 'A'), module_docs::RULE11A),
+    (RuleCode::new(11, 'B'), module_docs::RULE11B),
+    (RuleCode::new(11, 'E'), module_docs::RULE11E),
+    (RuleCode::new(12, 'A'), module_docs::RULE12A),
+    (RuleCode::westwood('B'), module_docs::RULEWB),
+    (RuleCode::westwood('C'), module_docs::RULEWC),
+    (RuleCode::westwood('D'), module_docs::RULEWD),
+    (RuleCode::westwood('E'), module_docs::RULEWE),
+    (RuleCode::westwood('F'), module_docs::RULEWF),
+];
+
+/// Returns the text of the code standard for the rule with the given code, as quoted in the
+/// rule's module documentation. Common indentation is removed.
+///
+/// Returns [`None`] if there is no rule with the given code.
+#[must_use]
+pub fn standard_text(code: RuleCode) -> Option<String> {
+    let lines: Vec<&str> = module_doc(code)?
+        .skip_while(|line| *line != "```text")
+        .skip(1)
+        .take_while(|line| *line != "```")
+        .collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or(0);
+    let mut text = String::new();
+    for line in lines {
+        text.push_str(line.get(indent..).unwrap_or_default());
+        text.push('\n');
+    }
+    Some(text)
+}
+
+/// Returns the "Implementation notes" section of the rule's module documentation, if it has one.
+#[must_use]
+pub fn implementation_notes(code: RuleCode) -> Option<String> {
+    let lines: Vec<&str> = module_doc(code)?
+        .skip_while(|line| *line != "# Implementation notes")
+        .skip(1)
+        .take_while(|line| !line.starts_with("# "))
+        .collect();
+    let notes = lines.join("\n").trim().to_owned();
+    (!notes.is_empty()).then_some(notes)
+}
+
+/// Extracts the examples from the text of a code standard rule, as returned by
+/// [`standard_text()`].
+///
+/// An example starts with `Example:` and continues until the end of its paragraph. Following
+/// paragraphs which are indented past the start of the `Example:` line are also part of the
+/// example, since examples of code often contain blank lines.
+#[must_use]
+pub fn examples(standard: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut lines = standard.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(first) = line.trim_start().strip_prefix("Example:") else {
+            continue;
+        };
+        let indent = indentation(line);
+        let first = first.trim_start();
+        // Column at which the example's text starts
+        let body_indent = line.len() - first.len();
+
+        let mut example = first.to_owned();
+        let mut blank_lines = 0;
+        while let Some(next) = lines.next_if(|next| {
+            next.trim().is_empty()
+                || !(next.trim_start().starts_with("Example:")
+                    || (blank_lines > 0 && indentation(next) <= indent))
+        }) {
+            if next.trim().is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            for _ in 0..=blank_lines {
+                example.push('\n');
+            }
+            blank_lines = 0;
+            if indentation(next) >= body_indent {
+                example.push_str(&next[body_indent..]);
+            } else {
+                example.push_str(next.trim_start());
+            }
+        }
+        examples.push(example);
+    }
+    examples
+}
+
+/// Returns the number of bytes of whitespace at the start of `line`.
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Returns the lines of the module-level documentation of the rule with the given code, or
+/// [`None`] if there is no rule with the given code.
+fn module_doc(code: RuleCode) -> Option<impl Iterator<Item = &'static str>> {
+    RULE_DOCS
+        .iter()
+        .find(|(rule, _doc)| *rule == code)
+        .map(|(_code, doc)| doc.lines())
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        config::Config,
+        rules::{api::RuleCode, get_rules},
+    };
+
+    use super::{examples, implementation_notes, standard_text};
+
+    #[test]
+    fn standard_text_extraction() {
+        let expected = indoc! {"
+            A. Do not use tabs for indentation.
+        "};
+        assert_eq!(Some(expected.to_owned()), standard_text(RuleCode::new(11, 'A')));
+        assert_eq!(None, standard_text(RuleCode::new(11, 'Z')));
+    }
+
+    #[test]
+    fn implementation_notes_extraction() {
+        let notes = implementation_notes(RuleCode::new(1, 'B')).unwrap();
+        assert!(notes.starts_with("This is almost impossible"), "{notes}");
+        assert!(notes.ends_with("for the sake of completeness."), "{notes}");
+        assert_eq!(None, implementation_notes(RuleCode::new(11, 'B')));
+    }
+
+    /// Tests that examples spanning several lines and paragraphs are extracted whole.
+    #[test]
+    fn examples_extraction() {
+        let standard = indoc! {"
+            D. Something about #defines.
+
+               Example: #include \"hw1.h\"
+
+                        #define FOO (1)
+
+               Example: for (i = 0;
+                             i < n; i++)
+
+               Example: A prose example which
+               continues on the next line.
+
+               Also not part of the example.
+        "};
+        assert_eq!(
+            vec![
+                "#include \"hw1.h\"\n\n#define FOO (1)",
+                "for (i = 0;\n     i < n; i++)",
+                "A prose example which\ncontinues on the next line.",
+            ],
+            examples(standard)
+        );
+    }
+
+    /// Tests that every rule has documentation.
+    #[test]
+    fn all_rules_documented() {
+        for rule in get_rules(&Config::default()) {
+            let text = standard_text(rule.code());
+            assert!(text.is_some_and(|text| !text.is_empty()), "{}", rule.code());
+        }
+    }
+}
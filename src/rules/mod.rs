@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod api;
+pub mod docs;
 pub mod rule01a;
 pub mod rule01b;
 pub mod rule01c;
@@ -29,30 +30,159 @@ pub mod rule11a;
 pub mod rule11b;
 pub mod rule11e;
 pub mod rule12a;
+pub mod rulewb;
+pub mod rulewc;
+pub mod rulewd;
+pub mod rulewe;
+pub mod rulewf;
 
-use std::num::NonZeroUsize;
+use codespan_reporting::diagnostic::{Diagnostic, Severity};
 
-use self::api::Rule;
+use crate::{
+    config::{Config, RuleConfig},
+    suppress::Suppressions,
+    syntax::SyntaxErrors,
+};
+
+use self::api::{Lint, Rule, RuleCode, SourceInfo};
 
 #[must_use]
-/// Returns a [Vec] of all [rules][Rule].
-pub fn get_rules() -> Vec<Box<dyn Rule>> {
-    vec![
-        Box::new(rule01a::Rule01a {}),
-        Box::new(rule01b::Rule01b {}),
-        Box::new(rule01c::Rule01c {}),
-        Box::new(rule01d::Rule01d {}),
-        Box::new(rule02a::Rule02a {}),
-        Box::new(rule02b::Rule02b {}),
-        Box::new(rule03a::Rule03a {}),
-        Box::new(rule03b::Rule03b {}),
-        Box::new(rule03c::Rule03c {}),
-        Box::new(rule03d::Rule03d {}),
-        Box::new(rule03e::Rule03e {}),
-        Box::new(rule03f::Rule03f {}),
-        Box::new(rule11a::Rule11a::new(Some(3))),
-        Box::new(rule11b::Rule11b::new(Some(NonZeroUsize::new(3).unwrap()))),
-        Box::new(rule11e::Rule11e {}),
-        Box::new(rule12a::Rule12a {}),
+/// Returns a [Vec] of all [rules][Rule] which are enabled in the given configuration.
+pub fn get_rules(config: &Config) -> Vec<Box<dyn Rule>> {
+    let rules = &config.rules;
+    let tab_width = config.tab_width;
+    [
+        configure(&rules.rule01a, rule01a::Rule01a {}),
+        configure(&rules.rule01b, rule01b::Rule01b {}),
+        configure(&rules.rule01c, rule01c::Rule01c {}),
+        configure(&rules.rule01d, rule01d::Rule01d {}),
+        configure(&rules.rule02a, rule02a::Rule02a::new(rules.rule02a.params.clone(), tab_width)),
+        configure(&rules.rule02b, rule02b::Rule02b::new(rules.rule02b.params.clone())),
+        configure(&rules.rule03a, rule03a::Rule03a {}),
+        configure(&rules.rule03b, rule03b::Rule03b {}),
+        configure(&rules.rule03c, rule03c::Rule03c {}),
+        configure(&rules.rule03d, rule03d::Rule03d {}),
+        configure(&rules.rule03e, rule03e::Rule03e {}),
+        configure(&rules.rule03f, rule03f::Rule03f {}),
+        configure(
+            &rules.rule11a,
+            rule11a::Rule11a::new(
+                rules.rule11a.params.clone(),
+                tab_width,
+                rules.rule02a.params.indent_width,
+            ),
+        ),
+        configure(&rules.rule11b, rule11b::Rule11b::new(rules.rule11b.params.clone())),
+        configure(&rules.rule11e, rule11e::Rule11e {}),
+        configure(&rules.rule12a, rule12a::Rule12a {}),
+        configure(&rules.rulewb, rulewb::RuleWb::new(rules.rulewb.params.clone())),
+        configure(&rules.rulewc, rulewc::RuleWc::new(rules.rulewc.params.clone())),
+        configure(&rules.rulewd, rulewd::RuleWd::new(rules.rulewd.params.clone())),
+        configure(&rules.rulewe, rulewe::RuleWe::new(rules.rulewe.params.clone())),
+        configure(&rules.rulewf, rulewf::RuleWf::new(rules.rulewf.params.clone())),
     ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Returns the codes of all rules, whether or not they are enabled.
+#[must_use]
+pub fn all_codes() -> Vec<RuleCode> {
+    get_rules(&Config::default()).iter().map(|rule| rule.code()).collect()
+}
+
+/// Applies the options common to all rules. Returns [`None`] if the rule is disabled.
+fn configure<P>(config: &RuleConfig<P>, rule: impl Rule + 'static) -> Option<Box<dyn Rule>> {
+    if !config.enabled {
+        return None;
+    }
+    Some(match config.severity {
+        Some(severity) => Box::new(WithSeverity {
+            rule,
+            severity: severity.into(),
+        }),
+        None => Box::new(rule),
+    })
+}
+
+/// Wrapper around a rule which changes the severity of the diagnostics it produces.
+struct WithSeverity<R: Rule> {
+    rule: R,
+    severity: Severity,
+}
+
+impl<R: Rule> Rule for WithSeverity<R> {
+    fn code(&self) -> RuleCode {
+        self.rule.code()
+    }
+
+    fn title(&self) -> &'static str {
+        self.rule.title()
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.rule.default_severity()
+    }
+
+    fn is_fixable(&self) -> bool {
+        self.rule.is_fixable()
+    }
+
+    fn is_checked(&self) -> bool {
+        self.rule.is_checked()
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, source: &SourceInfo) -> Vec<Lint> {
+        let mut lints = self.rule.lint(source);
+        for lint in &mut lints {
+            lint.diagnostic.severity = self.severity;
+        }
+        lints
+    }
+}
+
+/// Checks the given source file using the given rules.
+///
+/// Each lint's diagnostic is given the code of the rule which produced it.
+///
+/// Lints whose diagnostics are suppressed by comments in the source are removed. If
+/// `report_unused_suppressions` is `true`, a warning is added for each suppression comment which
+/// did not suppress anything from the given rules or names an unknown rule. See
+/// [`crate::suppress`].
+///
+/// If the source contains syntax errors, an error is added for each one, and lints in the regions
+/// affected by them are removed. See [`crate::syntax`].
+#[must_use]
+pub fn check_source(
+    source: &SourceInfo,
+    rules: &[Box<dyn Rule>],
+    report_unused_suppressions: bool,
+) -> Vec<Lint> {
+    let syntax_errors = SyntaxErrors::new(source);
+    let mut suppressions = Suppressions::new(source);
+    // Suppressions are applied before discarding lints affected by syntax errors so that
+    // suppressions of those lints aren't reported as unused.
+    let mut lints: Vec<Lint> = rules
+        .iter()
+        .flat_map(|rule| {
+            let code = rule.code().to_string();
+            rule.lint(source).into_iter().map(move |mut lint| {
+                lint.diagnostic.code = Some(code.clone());
+                lint
+            })
+        })
+        .filter(|lint| !suppressions.is_suppressed(&lint.diagnostic))
+        .filter(|lint| !syntax_errors.is_affected(&lint.diagnostic))
+        .collect();
+    if report_unused_suppressions {
+        let checked: Vec<RuleCode> = rules.iter().map(|rule| rule.code()).collect();
+        lints.extend(suppressions.unused(&checked).into_iter().map(Lint::from));
+    }
+    lints.extend(syntax_errors.diagnostics.into_iter().map(Lint::from));
+    lints
 }
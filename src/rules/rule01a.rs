@@ -35,7 +35,7 @@ use indoc::indoc;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 const QUERY_STR: &str = indoc! { /* query */ r#"
     (
@@ -66,6 +66,14 @@ const QUERY_STR: &str = indoc! { /* query */ r#"
 pub struct Rule01a {}
 
 impl Rule for Rule01a {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(1, 'A')
+    }
+
+    fn title(&self) -> &'static str {
+        "Variable names must be lowercase, with words separated by underscores"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let mut diagnostics = Vec::new();
@@ -80,7 +88,6 @@ impl Rule for Rule01a {
             };
             let diagnostic = Diagnostic::warning()
                 .with_message(format!("{nametype} names must be in lower snake case."))
-                .with_code("I:A")
                 .with_label(
                     Label::primary((), capture.node.byte_range())
                         .with_message("Name contains uppercase character(s)"),
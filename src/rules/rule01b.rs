@@ -37,7 +37,7 @@
 
 use codespan_reporting::diagnostic::Diagnostic;
 
-use crate::rules::api::{Rule, SourceInfo};
+use crate::rules::api::{Rule, RuleCode, SourceInfo};
 
 /// # Rule I:B.
 ///
@@ -45,6 +45,18 @@ use crate::rules::api::{Rule, SourceInfo};
 pub struct Rule01b {}
 
 impl Rule for Rule01b {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(1, 'B')
+    }
+
+    fn title(&self) -> &'static str {
+        "Use descriptive and meaningful names"
+    }
+
+    fn is_checked(&self) -> bool {
+        false
+    }
+
     fn check(&self, _: &SourceInfo) -> Vec<Diagnostic<()>> {
         Vec::with_capacity(0)
     }
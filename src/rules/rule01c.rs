@@ -44,7 +44,7 @@ use tree_sitter::QueryCapture;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule I:C.
 const QUERY_STR: &str = indoc! { /* query */ r#"
@@ -68,6 +68,14 @@ const QUERY_STR: &str = indoc! { /* query */ r#"
 pub struct Rule01c {}
 
 impl Rule for Rule01c {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(1, 'C')
+    }
+
+    fn title(&self) -> &'static str {
+        "Constants must be uppercase #defines with parenthesized values"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let mut diagnostics = Vec::new();
@@ -92,7 +100,6 @@ impl Rule for Rule01c {
                 _ => unreachable!(),
             };
             let mut diagnostic = Diagnostic::warning()
-                .with_code("I:C")
                 .with_message(message)
                 .with_label(Label::primary((), capture.node.byte_range()).with_message(label));
             if let Some(fix) = fix {
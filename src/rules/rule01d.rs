@@ -36,7 +36,7 @@ use tree_sitter::QueryCapture;
 
 use crate::{
     helpers::QueryHelper,
-    rules::api::{Rule, SourceInfo},
+    rules::api::{Rule, RuleCode, SourceInfo},
 };
 
 /// Tree-sitter query for Rule I:D.
@@ -67,6 +67,14 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule01d {}
 
 impl Rule for Rule01d {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(1, 'D')
+    }
+
+    fn title(&self) -> &'static str {
+        "Global variables must start with `g_' and be declared at the top of the file"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let mut first_function_position = None;
@@ -86,7 +94,6 @@ impl Rule for Rule01d {
                 "global.no_g_prefix" => {
                     let message = "Global variables must be prefixed with `g_'";
                     Diagnostic::warning()
-                        .with_code("I:D")
                         .with_message(message)
                         .with_label(
                             Label::primary((), capture.node.byte_range())
@@ -100,7 +107,6 @@ impl Rule for Rule01d {
                     let message =
                         "All top-level declarations must come before function definitions";
                     Diagnostic::warning()
-                        .with_code("I:D")
                         .with_message(message)
                         .with_label(
                             Label::primary((), capture.node.byte_range())
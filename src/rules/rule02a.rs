@@ -32,23 +32,75 @@
 //!      Example: fread(&value, sizeof(double),
 //!                     1, special_fp);
 //! ```
+//!
+//! # Implementation notes
+//!
+//! In addition to the line length and wrapped line checks described by the rule above, this rule
+//! checks the indentation of every line which begins a statement, declaration, or comment. The
+//! expected indentation is the nesting depth of the line multiplied by the indentation width
+//! (2 spaces by default). The nesting depth is increased by one
+//!  - inside of braces (compound statements, struct/union bodies, and enum bodies),
+//!  - for the body of an `if`/`else`/loop statement which is not surrounded by braces, and
+//!  - for the statements following a `case`/`default` label, which are themselves nested inside
+//!    the `switch` statement's braces.
+//!
+//! Lines which continue a wrapped statement are not checked against the nesting depth; they are
+//! only required to be indented further than the first line of the statement, as described
+//! above. Preprocessor directives are not checked, since Rule III:D requires them to be placed in
+//! column 1, but code inside `#if`/`#ifdef` blocks is checked as if the directives weren't there.
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indoc::indoc;
-use tree_sitter::Range;
-use unicode_width::UnicodeWidthStr;
-
-use crate::{helpers::QueryHelper, rules::api::Rule};
-
-use crate::rules::api::SourceInfo;
+use serde::Deserialize;
+use tree_sitter::{Node, Range, Tree};
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    helpers::{next_tab_stop, text_width, QueryHelper},
+    rules::api::Rule,
+};
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Parameters for [Rule II:A][Rule02a].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Rule02aConfig {
+    /// Maximum width of a line, in columns.
+    pub max_line_length: usize,
+    /// Number of columns by which each level of nesting must be indented.
+    pub indent_width: usize,
+    /// Amount that wrapped lines must be indented, in columns.
+    pub wrapped_indent_width: usize,
+}
 
-/// Amount that wrapped lines must be indented, in columns.
-const WRAPPED_LINE_INDENT_WIDTH: usize = 2;
+impl Default for Rule02aConfig {
+    fn default() -> Self {
+        Self {
+            max_line_length: 80,
+            indent_width: 2,
+            wrapped_indent_width: 2,
+        }
+    }
+}
 
 /// # Rule II:A.
 ///
 /// See module-level documentation for details.
-pub struct Rule02a {}
+pub struct Rule02a {
+    config: Rule02aConfig,
+    tab_width: usize,
+}
+
+impl Rule02a {
+    /// Constructs a new instance of this rule.
+    ///
+    /// `tab_width` specifies the width of a tab character, in columns.
+    #[must_use]
+    pub fn new(config: Rule02aConfig, tab_width: usize) -> Self {
+        Self { config, tab_width }
+    }
+}
 
 /// Tree-sitter query for Rule II:A.
 const QUERY_STR: &str = indoc! { /* query */ r##"
@@ -105,17 +157,29 @@ const QUERY_STR: &str = indoc! { /* query */ r##"
 "## };
 
 impl Rule for Rule02a {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(2, 'A')
+    }
+
+    fn title(&self) -> &'static str {
+        "Lines must fit within the column limit and be indented consistently"
+    }
+
     fn check(&self, SourceInfo { tree, code, lines }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let mut diagnostics = Vec::new();
 
-        // Check for lines >80 columns long
+        // Check for lines which are too long
+        let max_line_length = self.config.max_line_length;
         for (line, index) in lines {
-            let width = line_width(line);
-            if width > 80 {
+            let width = text_width(line, self.tab_width);
+            if width > max_line_length {
                 let diagnostic = Diagnostic::warning()
-                    .with_code("II:A")
-                    .with_message("Line length exceeds 80 columns.")
-                    .with_label(Label::primary((), (index + 80)..(index + line.len())));
+                    .with_message(format!("Line length exceeds {max_line_length} columns."))
+                    .with_label(Label::primary(
+                        (),
+                        (index + overflow_offset(line, self.tab_width, max_line_length))
+                            ..(index + line.len()),
+                    ));
                 diagnostics.push(diagnostic);
             }
         }
@@ -160,12 +224,12 @@ impl Rule for Rule02a {
             .take(range.end_point.row + 1 - range.start_point.row);
             let &(first_line, first_line_byte_pos) = code_lines.next().unwrap();
             let first_line_indent = get_indentation(first_line);
-            let first_line_indent_width = line_width(first_line_indent);
-            let expected_indent_width = first_line_indent_width + WRAPPED_LINE_INDENT_WIDTH;
+            let first_line_indent_width = text_width(first_line_indent, self.tab_width);
+            let expected_indent_width = first_line_indent_width + self.config.wrapped_indent_width;
             let mut labels = Vec::new();
             for &(this_line, this_line_pos) in &mut code_lines {
                 let this_line_indent = get_indentation(this_line);
-                let this_line_indent_width = line_width(this_line_indent);
+                let this_line_indent_width = text_width(this_line_indent, self.tab_width);
                 if this_line_indent_width < expected_indent_width {
                     labels.push(
                         Label::primary((), this_line_pos..(this_line_pos + this_line_indent.len()))
@@ -183,9 +247,9 @@ impl Rule for Rule02a {
 
             diagnostics.push(
                 Diagnostic::warning()
-                    .with_code("II:A")
                     .with_message(format!(
-                        "Wrapped expressions/statements must be indented by at least {WRAPPED_LINE_INDENT_WIDTH} spaces",
+                        "Wrapped expressions/statements must be indented by at least {} spaces",
+                        self.config.wrapped_indent_width,
                     ))
                     .with_labels(labels)
                     .with_label(
@@ -200,16 +264,225 @@ impl Rule for Rule02a {
             );
         });
 
+        // Check indentation of each line based on nesting depth
+        for (row, expected) in expected_indentation(tree, lines, self.config.indent_width) {
+            let (line, line_start) = lines[row];
+            let indentation = get_indentation(line);
+            let actual = text_width(indentation, self.tab_width);
+            if actual == expected {
+                continue;
+            }
+            let range = if indentation.is_empty() {
+                // Label the first character if there is no indentation to label
+                let first_char_len = line.chars().next().map_or(0, char::len_utf8);
+                line_start..(line_start + first_char_len)
+            } else {
+                line_start..(line_start + indentation.len())
+            };
+            let label_message = if actual > expected {
+                "Line is over-indented"
+            } else {
+                "Line is under-indented"
+            };
+            diagnostics.push(
+                Diagnostic::warning()
+                    .with_message(format!(
+                        "Expected {expected} columns of indentation, but found {actual}"
+                    ))
+                    .with_label(Label::primary((), range).with_message(label_message))
+                    .with_note(format!(
+                        "Each level of nesting must be indented by {} spaces",
+                        self.config.indent_width
+                    )),
+            );
+        }
+
         diagnostics
     }
 }
 
-/// Returns the width of a line in columns.
+/// Returns the expected indentation, in columns, of each line which begins a statement,
+/// declaration, or comment, based on the line's nesting depth. Each element is a pair of a row
+/// (line index) and its expected indentation, in the order the lines are visited.
 ///
-/// Returns the width according to the [`unicode_width`] module, but with tab characters (U+0009 or
-/// `'\t'`) treated as 8 columns wide.
-fn line_width(line: &str) -> usize {
-    line.width() + line.chars().filter(|c| *c == '\t').count() * 7
+/// Lines which continue a wrapped statement have no expected indentation, so they are not
+/// included. See the [module-level documentation][self] for details.
+#[must_use]
+pub fn expected_indentation(
+    tree: &Tree,
+    lines: &[(&str, usize)],
+    indent_width: usize,
+) -> Vec<(usize, usize)> {
+    let mut walker = IndentWalker {
+        lines,
+        indent_width,
+        expected: Vec::new(),
+    };
+    for child in tree.root_node().named_children(&mut tree.walk()) {
+        walker.visit_statement(child, 0);
+    }
+    walker.expected
+}
+
+/// Walks a syntax tree, keeping track of the nesting depth, and records the expected indentation
+/// of each line which begins a statement, declaration, or comment.
+struct IndentWalker<'a, 'src> {
+    lines: &'a [(&'src str, usize)],
+    indent_width: usize,
+    expected: Vec<(usize, usize)>,
+}
+
+impl IndentWalker<'_, '_> {
+    /// Visits a node which appears where statements/declarations do, e.g. inside of braces.
+    fn visit_statement(&mut self, node: Node, depth: usize) {
+        match node.kind() {
+            "compound_statement" | "field_declaration_list" | "enumerator_list" => {
+                self.visit_block(node, depth);
+            }
+
+            // Check the code inside conditional compilation blocks, but not the directives
+            // themselves
+            "preproc_if" | "preproc_ifdef" | "preproc_elif" | "preproc_elifdef"
+            | "preproc_else" => {
+                let directive_row = node.start_position().row;
+                for child in node.named_children(&mut node.walk()) {
+                    if child.start_position().row != directive_row {
+                        self.visit_statement(child, depth);
+                    }
+                }
+            }
+            kind if kind.starts_with("preproc_") => (),
+
+            "if_statement" => {
+                self.record_line(node, depth);
+                let consequence = node.child_by_field_name("consequence");
+                for child in node.children(&mut node.walk()) {
+                    match child.kind() {
+                        "else_clause" => {
+                            self.record_line(child, depth);
+                            for body in child.named_children(&mut child.walk()) {
+                                // Don't nest `else if` chains
+                                if body.kind() == "if_statement" {
+                                    self.visit_statement(body, depth);
+                                } else {
+                                    self.visit_body(body, depth);
+                                }
+                            }
+                        }
+                        _ if Some(child) == consequence => {
+                            self.visit_body(child, depth);
+                        }
+                        _ => self.visit_continuation(child, depth),
+                    }
+                }
+            }
+
+            "while_statement" | "for_statement" | "do_statement" | "switch_statement" => {
+                self.record_line(node, depth);
+                let body = node.child_by_field_name("body");
+                for child in node.children(&mut node.walk()) {
+                    if Some(child) == body {
+                        self.visit_body(child, depth);
+                    } else if child.kind() == "while" {
+                        // The `while` of a do-while loop
+                        self.record_line(child, depth);
+                    } else {
+                        self.visit_continuation(child, depth);
+                    }
+                }
+            }
+
+            "case_statement" => {
+                self.record_line(node, depth);
+                let value = node.child_by_field_name("value");
+                for child in node.children(&mut node.walk()) {
+                    if !child.is_named() || Some(child) == value {
+                        self.visit_continuation(child, depth);
+                    } else if child.kind() == "compound_statement"
+                        && child.start_position().row == node.start_position().row
+                    {
+                        // Braces on the same line as the label, e.g. `case 1: {`
+                        self.visit_block(child, depth);
+                    } else {
+                        self.visit_statement(child, depth + 1);
+                    }
+                }
+            }
+
+            "labeled_statement" => {
+                self.record_line(node, depth);
+                for child in node.named_children(&mut node.walk()) {
+                    self.visit_statement(child, depth);
+                }
+            }
+
+            _ => {
+                self.record_line(node, depth);
+                self.visit_continuation(node, depth);
+            }
+        }
+    }
+
+    /// Visits the body of an `if`/`else`/loop/`switch` statement.
+    fn visit_body(&mut self, body: Node, depth: usize) {
+        if body.kind() == "compound_statement" {
+            self.visit_block(body, depth);
+        } else {
+            self.visit_statement(body, depth + 1);
+        }
+    }
+
+    /// Visits a node surrounded by braces. The braces are expected at the given depth and the
+    /// contents are expected one level deeper.
+    fn visit_block(&mut self, node: Node, depth: usize) {
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "{" | "}" => self.record_line(child, depth),
+                _ if child.is_named() => self.visit_statement(child, depth + 1),
+                _ => (),
+            }
+        }
+    }
+
+    /// Visits the inside of a node which continues a statement, e.g. an expression. Lines
+    /// beginning inside such a node are not checked, but it may contain blocks which must be,
+    /// e.g. the body of a struct in a declaration.
+    fn visit_continuation(&mut self, node: Node, depth: usize) {
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "compound_statement" | "field_declaration_list" | "enumerator_list" => {
+                    self.visit_block(child, depth);
+                }
+                _ => self.visit_continuation(child, depth),
+            }
+        }
+    }
+
+    /// Records the expected indentation of the line on which `node` starts, if `node` is the first
+    /// thing on that line.
+    fn record_line(&mut self, node: Node, depth: usize) {
+        let row = node.start_position().row;
+        let (line, line_start) = self.lines[row];
+        if node.start_byte() == line_start + get_indentation(line).len() {
+            self.expected.push((row, depth * self.indent_width));
+        }
+    }
+}
+
+/// Returns the byte offset of the first character of `line` which extends past `max_width`
+/// columns, measuring widths the same way as [`text_width()`].
+fn overflow_offset(line: &str, tab_width: usize, max_width: usize) -> usize {
+    let mut width = 0;
+    for (offset, c) in line.char_indices() {
+        width = match c {
+            '\t' => next_tab_stop(width, tab_width),
+            c => width + c.width().unwrap_or(0),
+        };
+        if width > max_width {
+            return offset;
+        }
+    }
+    line.len()
 }
 
 /// Returns the leading whitespace part of the line
@@ -229,29 +502,7 @@ mod tests {
         rules::api::{Rule, SourceInfo},
     };
 
-    use super::{Rule02a, QUERY_STR};
-
-    #[test]
-    fn line_width() {
-        let tests = [
-            ("", 0),
-            ("\t", 8),
-            ("\t\t", 16),
-            ("\tint x;", 14),
-            (
-                "static void read_line(const char *restrict, char *restrict, size_t);",
-                68,
-            ),
-            (
-                "static void read_line(const char *restrict prompt, char *restrict buffer, size_t buffer_size);",
-                94,
-            ),
-            ("int 😵 = 5;", 11),
-        ];
-        for (line, expected) in tests {
-            assert_eq!(expected, super::line_width(line));
-        }
-    }
+    use super::{Rule02a, Rule02aConfig, QUERY_STR};
 
     #[test]
     fn test_rule02a_captures() -> ExitCode {
@@ -318,7 +569,7 @@ mod tests {
 
     #[test]
     fn test_rule02a_diagnostics() {
-        let rule = Rule02a {};
+        let rule = Rule02a::new(Rule02aConfig::default(), 8);
 
         macro_rules! test {
             ($code:literal, $ndiag:expr, $nlabels_list:expr) => {
@@ -376,4 +627,125 @@ mod tests {
         test!("#define MAX(a, b) \\\n((a) < (b) ? (a) : (b))", 1, [2]);
         test!("#define MAX(a, b) \\\n  ((a) < (b) ? (a) : (b))", 0, []);
     }
+
+    /// Tests the indentation checks based on nesting depth. Lines ending in `// bad` are expected
+    /// to produce diagnostics.
+    #[test]
+    fn indentation() {
+        let code = indoc! { /* c */ r#"
+            #ifndef FOO
+            int g_x;
+            #endif
+
+            struct point {
+              int x;
+               int y; // bad
+            };
+
+            /*
+             * Comment
+             */
+            int main(int argc,
+                     char **argv) {
+              int i = 0;
+              if (argc > 1) {
+                printf("hi\n");
+              }
+              else if (argc > 2)
+                return 1;
+              else {
+                  return 2; // bad
+              }
+              for (i = 0; i < 10; i++)
+                i++;
+              while (i)
+              i--; // bad
+              do {
+                i++;
+              } while (i < 3);
+              switch (i) {
+                case 1:
+                  break;
+                case 2: {
+                  int y = 0;
+                  break;
+                }
+                default:
+                break; // bad
+              }
+              {
+                int z = 0;
+              }
+              printf("%d %d\n",
+                i,
+                i);
+             return 0; // bad
+            }
+        "# };
+        let expected_rows: Vec<usize> = code
+            .lines()
+            .enumerate()
+            .filter(|(_row, line)| line.ends_with("// bad"))
+            .map(|(row, _line)| row)
+            .collect();
+        let source = SourceInfo::new(code);
+        let diagnostics = Rule02a::new(Rule02aConfig::default(), 8).check(&source);
+        let actual_rows: Vec<usize> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let start = diagnostic.labels[0].range.start;
+                code[..start].matches('\n').count()
+            })
+            .collect();
+        assert_eq!(expected_rows, actual_rows);
+    }
+
+    /// Tests that the indentation width can be changed.
+    #[test]
+    fn indentation_width() {
+        let code = "int main() {\n    if (1) {\n        return 0;\n    }\n}\n";
+        let source = SourceInfo::new(code);
+        let config = Rule02aConfig {
+            indent_width: 4,
+            ..Default::default()
+        };
+        assert!(Rule02a::new(config, 8).check(&source).is_empty());
+        assert_eq!(3, Rule02a::new(Rule02aConfig::default(), 8).check(&source).len());
+    }
+
+    /// Tests that the maximum line length and tab width can be changed.
+    #[test]
+    fn max_line_length() {
+        let code = format!("int main() {{\n\treturn {};\n}}\n", "1".repeat(70));
+        let source = SourceInfo::new(&code);
+        let config = Rule02aConfig {
+            max_line_length: 100,
+            indent_width: 8,
+            ..Default::default()
+        };
+        assert_eq!(2, Rule02a::new(Rule02aConfig::default(), 8).check(&source).len());
+        assert!(Rule02a::new(config.clone(), 8).check(&source).is_empty());
+        assert_eq!(1, Rule02a::new(config, 4).check(&source).len());
+
+        // Labels start at the first character past the limit, whatever its width in bytes
+        let rule = Rule02a::new(
+            Rule02aConfig {
+                max_line_length: 10,
+                ..Default::default()
+            },
+            4,
+        );
+        let code = "\t\t\tx = 12345;\n/* ééééééééééé */\nint\tx = 1234;\n";
+        let source = SourceInfo::new(code);
+        let ranges: Vec<_> = rule
+            .check(&source)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.message.starts_with("Line length"))
+            .map(|diagnostic| diagnostic.labels[0].range.clone())
+            .collect();
+        // Tabs are 4 columns wide, so the 11th column is part of the third tab. The 11th column of
+        // the second line is the 8th `é', which starts at byte 3 + 2 * 7. On the third line, the tab
+        // only advances to the tab stop at column 4, so the 11th column is the `3'.
+        assert_eq!(vec![2..13, (14 + 17)..(14 + 28), (43 + 10)..(43 + 13)], ranges);
+    }
 }
@@ -27,6 +27,7 @@
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indoc::indoc;
+use serde::Deserialize;
 use tree_sitter::QueryCapture;
 
 use crate::{
@@ -34,12 +35,7 @@ use crate::{
     rules::api::Rule,
 };
 
-use crate::rules::api::SourceInfo;
-
-/// Number of lines per page
-const PAGE_SIZE: usize = 61;
-/// Maximum number of pages a function definition may span
-const MAX_PAGES_PER_FUNCTION: usize = 2;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule I:D.
 const QUERY_STR: &str = indoc! {
@@ -49,34 +45,74 @@ const QUERY_STR: &str = indoc! {
     "
 };
 
+/// Parameters for [Rule II:B][Rule02b].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Rule02bConfig {
+    /// Number of lines per page
+    pub page_size: usize,
+    /// Maximum number of pages a function definition may span
+    pub max_pages: usize,
+}
+
+impl Default for Rule02bConfig {
+    fn default() -> Self {
+        Self {
+            page_size: 61,
+            max_pages: 2,
+        }
+    }
+}
+
 /// # Rule II:B.
 ///
 /// See module-level documentation for details.
-pub struct Rule02b {}
+pub struct Rule02b {
+    config: Rule02bConfig,
+}
+
+impl Rule02b {
+    /// Constructs a new instance of this rule.
+    #[must_use]
+    pub fn new(config: Rule02bConfig) -> Self {
+        Self { config }
+    }
+}
 
 impl Rule for Rule02b {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(2, 'B')
+    }
+
+    fn title(&self) -> &'static str {
+        "Functions should be at most two pages long"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let mut diagnostics = Vec::new();
+        let Rule02bConfig {
+            page_size,
+            max_pages,
+        } = self.config;
         helper.for_each_capture(|label: &str, capture: QueryCapture| match label {
             "function" => {
                 let start = capture.node.start_position();
                 let end = capture.node.end_position();
                 let length = end.row - start.row + 1;
-                if length > MAX_PAGES_PER_FUNCTION * PAGE_SIZE {
+                if length > max_pages * page_size {
                     let message = format!(
                         "Functions must fit on {} pages, i.e. be no longer than {} lines",
-                        MAX_PAGES_PER_FUNCTION,
-                        MAX_PAGES_PER_FUNCTION * PAGE_SIZE
+                        max_pages,
+                        max_pages * page_size
+                    );
+                    let label = match function_definition_name(capture.node, code) {
+                        Some(name) => format!("Function `{name}()' is {length} lines long"),
+                        None => format!("Function is {length} lines long"),
+                    };
+                    let diagnostic = Diagnostic::warning().with_message(message).with_label(
+                        Label::primary((), capture.node.byte_range()).with_message(label),
                     );
-                    let diagnostic =
-                        Diagnostic::warning().with_code("II:B").with_message(message).with_label(
-                            Label::primary((), capture.node.byte_range()).with_message(format!(
-                                "Function `{}()' is {} lines long",
-                                function_definition_name(capture.node, code),
-                                length
-                            )),
-                        );
                     diagnostics.push(diagnostic);
                 }
             }
@@ -93,33 +129,37 @@ mod tests {
     use codespan_reporting::diagnostic::{Diagnostic, Label};
     use pretty_assertions::assert_eq;
 
-    use super::{Rule02b, MAX_PAGES_PER_FUNCTION, PAGE_SIZE};
+    use super::{Rule02b, Rule02bConfig};
 
     #[test]
     fn rule02b() {
+        let Rule02bConfig {
+            page_size,
+            max_pages,
+        } = Rule02bConfig::default();
+
         // Generate long function
         let mut code = String::new();
         code.push_str("int main() {\n");
-        for _ in 0..(PAGE_SIZE * MAX_PAGES_PER_FUNCTION) {
+        for _ in 0..(page_size * max_pages) {
             code.push_str("  (void) 0;\n");
         }
         code.push_str("}\n");
 
         // Test for diagnostic
-        let rule02b = Rule02b {};
+        let rule02b = Rule02b::new(Rule02bConfig::default());
         let source = SourceInfo::new(&code);
         assert_eq!(
             rule02b.check(&source),
             vec![Diagnostic::warning()
-                .with_code("II:B")
                 .with_message(format!(
                     "Functions must fit on {} pages, i.e. be no longer than {} lines",
-                    MAX_PAGES_PER_FUNCTION,
-                    PAGE_SIZE * MAX_PAGES_PER_FUNCTION
+                    max_pages,
+                    page_size * max_pages
                 ))
                 .with_label(Label::primary((), 0..(code.len() - 1)).with_message(format!(
                     "Function `main()' is {} lines long",
-                    2 + MAX_PAGES_PER_FUNCTION * PAGE_SIZE
+                    2 + max_pages * page_size
                 )))]
         );
     }
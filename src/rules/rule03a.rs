@@ -30,7 +30,7 @@ use tree_sitter::Node;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule III:A.
 const QUERY_STR: &str = indoc! {
@@ -90,6 +90,14 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule03a {}
 
 impl Rule for Rule03a {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'A')
+    }
+
+    fn title(&self) -> &'static str {
+        "Put one space after control flow keywords and before opening braces"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let mut diagnostics = Vec::new();
 
@@ -149,7 +157,6 @@ fn check_single_space_between(
     }
     Some(
         Diagnostic::warning()
-            .with_code("III:A")
             .with_message(message.to_owned())
             .with_label(Label::primary((), left.start_byte()..right.end_byte())),
     )
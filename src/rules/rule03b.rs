@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! # Rule III:A
+//! # Rule III:B
 //!
 //! ```text
 //!    B. One space must be placed before and after all logical, and
@@ -30,13 +30,15 @@
 //!       Example: *value = head->data;
 //! ```
 
+use std::ops::Range;
+
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indoc::indoc;
 use tree_sitter::Node;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{Edit, Lint, RuleCode, SourceInfo};
 
 /// Tree-sitter query to capture binary expressions/operators.
 const QUERY_STR_BINARY: &str = indoc! {
@@ -97,8 +99,20 @@ const QUERY_STR_FIELD: &str = indoc! {
 pub struct Rule03b {}
 
 impl Rule for Rule03b {
-    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
-        let mut diagnostics = Vec::new();
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'B')
+    }
+
+    fn title(&self) -> &'static str {
+        "Put one space on each side of binary operators"
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Lint> {
+        let mut lints = Vec::new();
 
         // Binary expressions
         let helper = QueryHelper::new(QUERY_STR_BINARY, tree, code);
@@ -110,8 +124,8 @@ impl Rule for Rule03b {
             let prev = helper.expect_node_for_capture_index(qmatch, prev_capture_i);
             let op = helper.expect_node_for_capture_index(qmatch, op_capture_i);
             let next = helper.expect_node_for_capture_index(qmatch, next_capture_i);
-            if let Some(diagnostic) = check_binary_op_spacing(op, prev, next, code) {
-                diagnostics.push(diagnostic);
+            if let Some(lint) = check_binary_op_spacing(op, prev, next, code) {
+                lints.push(lint);
             }
         });
 
@@ -125,12 +139,13 @@ impl Rule for Rule03b {
             let next = helper.expect_node_for_capture_index(qmatch, next_capture_i);
             // Nodes must be adjacent
             if op.end_byte() != next.start_byte() {
-                diagnostics.push(
-                    Diagnostic::warning()
-                        .with_code("III:B")
-                        .with_message("Expected no space after unary operator")
-                        .with_label(Label::primary((), op.end_byte()..next.start_byte())),
-                );
+                lints.push(spacing_lint(
+                    "Expected no space after unary operator",
+                    op.end_byte()..next.start_byte(),
+                    "Remove the space after the operator",
+                    "",
+                    code,
+                ));
             }
         });
 
@@ -148,12 +163,13 @@ impl Rule for Rule03b {
             let lbrack = helper.expect_node_for_capture_index(qmatch, lbrack_capture_i);
             // Nodes must be adjacent
             if prev.end_byte() != lbrack.start_byte() {
-                diagnostics.push(
-                    Diagnostic::warning()
-                        .with_code("III:B")
-                        .with_message("Expected no space before array subscript")
-                        .with_label(Label::primary((), prev.end_byte()..lbrack.start_byte())),
-                );
+                lints.push(spacing_lint(
+                    "Expected no space before array subscript",
+                    prev.end_byte()..lbrack.start_byte(),
+                    "Remove the space before the subscript",
+                    "",
+                    code,
+                ));
             }
         });
 
@@ -167,23 +183,18 @@ impl Rule for Rule03b {
             let prev = helper.expect_node_for_capture_index(qmatch, prev_capture_i);
             let op = helper.expect_node_for_capture_index(qmatch, op_capture_i);
             let next = helper.expect_node_for_capture_index(qmatch, next_capture_i);
-            if let Some(diagnostic) = check_field_op_spacing(op, prev, next) {
-                diagnostics.push(diagnostic);
+            if let Some(lint) = check_field_op_spacing(op, prev, next, code) {
+                lints.push(lint);
             }
         });
 
-        diagnostics
+        lints
     }
 }
 
-/// Checks the spacing around a binary operator. Returns a [Diagnostic] if the spacing is
-/// incorrect. Otherwise returns [None].
-fn check_binary_op_spacing(
-    op: Node,
-    left: Node,
-    right: Node,
-    code: &str,
-) -> Option<Diagnostic<()>> {
+/// Checks the spacing around a binary operator. Returns a [Lint] if the spacing is incorrect.
+/// Otherwise returns [None].
+fn check_binary_op_spacing(op: Node, left: Node, right: Node, code: &str) -> Option<Lint> {
     // If the adjacent items are on the same line, check that there's a single space between them.
     // If they're on separate lines, we do nothing, and leave it to Rule II:A to check the
     // indentation.
@@ -191,57 +202,95 @@ fn check_binary_op_spacing(
         && !is_single_space_between(left, op, code);
     let right_bad = op.end_position().row == right.start_position().row
         && !is_single_space_between(op, right, code);
-    let (message, range) = match (left_bad, right_bad) {
-        (true, true) => (
+    let op_text = &code[op.byte_range()];
+    let lint = match (left_bad, right_bad) {
+        (true, true) => spacing_lint(
             "Expected a single space on each side of binary operator",
             left.end_byte()..right.start_byte(),
+            "Put one space on each side of the operator",
+            &format!(" {op_text} "),
+            code,
         ),
-        (true, false) => {
-            ("Expected a single space before binary operator", left.end_byte()..op.end_byte())
-        }
-        (false, true) => (
+        (true, false) => spacing_lint(
+            "Expected a single space before binary operator",
+            left.end_byte()..op.end_byte(),
+            "Put one space before the operator",
+            &format!(" {op_text}"),
+            code,
+        ),
+        (false, true) => spacing_lint(
             "Expected a single space after binary operator",
             op.start_byte()..right.start_byte(),
+            "Put one space after the operator",
+            &format!("{op_text} "),
+            code,
         ),
         (false, false) => return None,
     };
-    Some(
-        Diagnostic::warning()
-            .with_code("III:B")
-            .with_message(message)
-            .with_label(Label::primary((), range)),
-    )
+    Some(lint)
 }
 
-/// Checks the spacing around a field access operator. Returns a [Diagnostic] if the spacing is
+/// Checks the spacing around a field access operator. Returns a [Lint] if the spacing is
 /// incorrect. Otherwise returns [None].
-fn check_field_op_spacing(op: Node, left: Node, right: Node) -> Option<Diagnostic<()>> {
+fn check_field_op_spacing(op: Node, left: Node, right: Node, code: &str) -> Option<Lint> {
     // If the adjacent items are on the same line, check that there's a single space between them.
     // If they're on separate lines, we do nothing, and leave it to Rule II:A to check the
     // indentation.
     let left_bad = left.end_byte() != op.start_byte();
     let right_bad = op.end_byte() != right.start_byte();
-    let (message, range) = match (left_bad, right_bad) {
-        (true, true) => (
+    let lint = match (left_bad, right_bad) {
+        (true, true) => spacing_lint(
             "Expected no space around field access operator",
             left.end_byte()..right.start_byte(),
+            "Remove the spaces around the operator",
+            &code[op.byte_range()],
+            code,
         ),
-        (true, false) => (
+        (true, false) => spacing_lint(
             "Expected no space before field access operator",
             left.end_byte()..op.start_byte(),
+            "Remove the space before the operator",
+            "",
+            code,
         ),
-        (false, true) => (
+        (false, true) => spacing_lint(
             "Expected no space after field access operator",
             op.end_byte()..right.start_byte(),
+            "Remove the space after the operator",
+            "",
+            code,
         ),
         (false, false) => return None,
     };
-    Some(
+    Some(lint)
+}
+
+/// Creates a lint for incorrect spacing in `range`, suggesting to replace the range with
+/// `replacement`.
+///
+/// The suggestion is omitted if it would change anything other than whitespace, e.g. if the range
+/// contains a comment.
+fn spacing_lint(
+    message: &str,
+    range: Range<usize>,
+    suggestion: &str,
+    replacement: &str,
+    code: &str,
+) -> Lint {
+    let non_whitespace =
+        |text: &str| text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let only_whitespace_changes =
+        non_whitespace(&code[range.clone()]) == non_whitespace(replacement);
+    let lint = Lint::from(
         Diagnostic::warning()
-            .with_code("III:B")
             .with_message(message)
-            .with_label(Label::primary((), range)),
-    )
+            .with_label(Label::primary((), range.clone())),
+    );
+    if only_whitespace_changes {
+        lint.with_suggestion(suggestion, Edit::replace(range, replacement))
+    } else {
+        lint
+    }
 }
 
 /// Returns `true` if there is a single space separating the two nodes, else `false`.
@@ -257,10 +306,15 @@ mod tests {
     use std::process::ExitCode;
 
     use indoc::indoc;
+    use pretty_assertions::assert_eq;
 
-    use crate::helpers::testing::test_captures;
+    use crate::{
+        fix::apply_edits,
+        helpers::testing::test_captures,
+        rules::api::{Rule, SourceInfo},
+    };
 
-    use super::{QUERY_STR_ARRAY, QUERY_STR_BINARY, QUERY_STR_FIELD, QUERY_STR_UNARY};
+    use super::{Rule03b, QUERY_STR_ARRAY, QUERY_STR_BINARY, QUERY_STR_FIELD, QUERY_STR_UNARY};
 
     #[test]
     fn binary_op_captures() -> ExitCode {
@@ -388,4 +442,36 @@ mod tests {
         };
         test_captures(QUERY_STR_ARRAY, input)
     }
+
+    /// Tests that applying the suggestions fixes the spacing, except where the suggestion would
+    /// also remove a comment.
+    #[test]
+    fn suggestions() {
+        let input = indoc! {"
+            int main() {
+              a = b+c;
+              a = b  <= c;
+              a = b /* comment */+ c;
+              a = - b [1];
+              s . t -> u;
+            }
+        "};
+        let expected = indoc! {"
+            int main() {
+              a = b + c;
+              a = b <= c;
+              a = b /* comment */+ c;
+              a = -b[1];
+              s.t->u;
+            }
+        "};
+        let source = SourceInfo::new(input);
+        let lints = Rule03b {}.lint(&source);
+        assert_eq!(7, lints.len());
+        let edits = lints
+            .iter()
+            .flat_map(|lint| &lint.suggestions)
+            .map(|suggestion| &suggestion.edit);
+        assert_eq!(expected, apply_edits(input, edits));
+    }
 }
@@ -26,9 +26,12 @@ use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indoc::indoc;
 use tree_sitter::Node;
 
-use crate::{helpers::QueryHelper, rules::api::Rule};
+use crate::{
+    helpers::QueryHelper,
+    rules::api::{Edit, Lint, Rule},
+};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule III:C.
 const QUERY_STR: &str = indoc! {
@@ -65,7 +68,23 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule03c {}
 
 impl Rule for Rule03c {
-    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'C')
+    }
+
+    fn title(&self) -> &'static str {
+        "Put one space after internal semicolons and commas"
+    }
+
+    fn is_fixable(&self) -> bool {
+        true
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Lint> {
         let mut diagnostics = Vec::new();
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let delim_capture_i = helper.expect_index_for_capture("delim");
@@ -80,12 +99,17 @@ impl Rule for Rule03c {
             }
 
             if !is_single_space_between(delim, next, code) {
-                diagnostics.push(
+                let mut lint = Lint::from(
                     Diagnostic::warning()
-                        .with_code("III:C")
                         .with_message("Expected one space after internal commas and semicolons")
                         .with_label(Label::primary((), delim.start_byte()..next.start_byte())),
                 );
+                // Only fix if there's nothing but whitespace (e.g. no comments) in between
+                let gap = delim.end_byte()..next.start_byte();
+                if code[gap.clone()].trim().is_empty() {
+                    lint = lint.with_fix([Edit::replace(gap, " ")]);
+                }
+                diagnostics.push(lint);
             }
         });
         diagnostics
@@ -107,9 +131,14 @@ mod tests {
 
     use indoc::indoc;
 
-    use crate::helpers::testing::test_captures;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        helpers::testing::test_captures,
+        rules::api::{Edit, Rule, SourceInfo},
+    };
 
-    use super::QUERY_STR;
+    use super::{Rule03c, QUERY_STR};
 
     #[test]
     fn rule03c_captures() -> ExitCode {
@@ -201,4 +230,13 @@ mod tests {
         };
         test_captures(QUERY_STR, input)
     }
+
+    /// Tests that missing and extra spaces are both fixed.
+    #[test]
+    fn fix() {
+        let code = "int main() {\n  f(a,b,  c);\n}\n";
+        let lints = Rule03c {}.lint(&SourceInfo::new(code));
+        let edits: Vec<Edit> = lints.into_iter().flat_map(|lint| lint.fix).collect();
+        assert_eq!(vec![Edit::replace(19..19, " "), Edit::replace(21..23, " ")], edits);
+    }
 }
@@ -52,7 +52,7 @@ use crate::{
     rules::api::Rule,
 };
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule III:D.
 const QUERY_STR: &str = indoc! {
@@ -75,6 +75,14 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule03d {}
 
 impl Rule for Rule03d {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'D')
+    }
+
+    fn title(&self) -> &'static str {
+        "#define directives must be grouped, aligned, and surrounded by blank lines"
+    }
+
     fn check(&self, SourceInfo { tree, code, lines }: &SourceInfo) -> Vec<Diagnostic<()>> {
         // List of function definition bodies
         let mut function_bodies: Vec<Node> = Vec::new();
@@ -116,7 +124,6 @@ impl Rule for Rule03d {
                     range_without_trailing_eol(group.start_byte..group.end_byte, code);
                 diagnostics.push(
                     Diagnostic::warning()
-                        .with_code("III:D")
                         .with_message("Global preprocessor definitions must be placed at the top of the file, before all functions")
                         .with_label(
                             Label::primary((), print_range).with_message("Macro(s) defined here")
@@ -133,7 +140,6 @@ impl Rule for Rule03d {
         if global_define_groups.len() > 1 {
             diagnostics.push(
                 Diagnostic::warning()
-                    .with_code("III:D")
                     .with_message("All top-level #define statements must be grouped together")
                     .with_labels_iter(global_define_groups.into_iter().enumerate().map(
                         |(i, group)| {
@@ -167,11 +173,15 @@ impl Rule for Rule03d {
                 let function_name = function_definition_name(function_def, code);
                 diagnostics.push(
                     Diagnostic::warning()
-                        .with_code("III:D")
                         .with_message(
                             "All #define statements in each function must be grouped together",
                         )
-                        .with_notes(vec![format!("In function `{}()'", function_name)])
+                        .with_notes(
+                            function_name
+                                .map(|name| format!("In function `{name}()'"))
+                                .into_iter()
+                                .collect(),
+                        )
                         .with_labels_iter(groups_in_function.into_iter().enumerate().map(
                             |(i, define_group)| {
                                 let range = define_group.start_byte..define_group.end_byte;
@@ -237,7 +247,6 @@ impl Rule for Rule03d {
                 (false, true) => {
                     diagnostics.push(
                         Diagnostic::warning()
-                            .with_code("III:D")
                             .with_message("Expected blank line before #define statement(s)")
                             .with_label(Label::primary((), print_range.clone()))
                             .with_label(
@@ -251,7 +260,6 @@ impl Rule for Rule03d {
                 (true, false) => {
                     diagnostics.push(
                         Diagnostic::warning()
-                            .with_code("III:D")
                             .with_message("Expected blank line after #define statement(s)")
                             .with_label(Label::primary((), print_range))
                             .with_label(
@@ -265,7 +273,6 @@ impl Rule for Rule03d {
                 (false, false) => {
                     diagnostics.push(
                         Diagnostic::warning()
-                            .with_code("III:D")
                             .with_message("Expected blank lines surrounding #define statement(s)")
                             .with_label(Label::primary((), print_range))
                             .with_label(
@@ -20,9 +20,9 @@
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 
-use crate::rules::api::Rule;
+use crate::rules::api::{Edit, Lint, Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// # Rule III:E.
 ///
@@ -30,7 +30,23 @@ use crate::rules::api::SourceInfo;
 pub struct Rule03e {}
 
 impl Rule for Rule03e {
-    fn check(&self, SourceInfo { lines, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'E')
+    }
+
+    fn title(&self) -> &'static str {
+        "Do not leave trailing whitespace at the end of a line"
+    }
+
+    fn is_fixable(&self) -> bool {
+        true
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { lines, .. }: &SourceInfo) -> Vec<Lint> {
         let mut diagnostics = Vec::new();
         for (line, index) in lines {
             let trimmed_line = line.trim_end();
@@ -39,10 +55,12 @@ impl Rule for Rule03e {
                 let start = index + trimmed_line.len();
                 let end = index + line.len();
                 diagnostics.push(
-                    Diagnostic::warning()
-                        .with_code("III:E")
-                        .with_message("Line contains trailing whitespace")
-                        .with_label(Label::primary((), start..end)),
+                    Lint::from(
+                        Diagnostic::warning()
+                            .with_message("Line contains trailing whitespace")
+                            .with_label(Label::primary((), start..end)),
+                    )
+                    .with_fix([Edit::delete(start..end)]),
                 );
             }
         }
@@ -52,7 +70,9 @@ impl Rule for Rule03e {
 
 #[cfg(test)]
 mod tests {
-    use crate::rules::api::{Rule, SourceInfo};
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Edit, Rule, SourceInfo};
 
     use super::Rule03e;
 
@@ -66,4 +86,13 @@ mod tests {
         let diagnostics = rule.check(&source);
         assert_eq!(2, diagnostics.len());
     }
+
+    /// Tests that the fixes remove the trailing whitespace.
+    #[test]
+    fn fix() {
+        let code = "int main() { \n  return 0;\t\n}\n";
+        let lints = Rule03e {}.lint(&SourceInfo::new(code));
+        let edits: Vec<Edit> = lints.into_iter().flat_map(|lint| lint.fix).collect();
+        assert_eq!(vec![Edit::delete(12..13), Edit::delete(25..26)], edits);
+    }
 }
@@ -22,9 +22,12 @@
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use indoc::indoc;
 
-use crate::{helpers::QueryHelper, rules::api::Rule};
+use crate::{
+    helpers::QueryHelper,
+    rules::api::{Edit, Lint, Rule},
+};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule III:F.
 const QUERY_STR: &str = indoc! {
@@ -48,7 +51,23 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule03f {}
 
 impl Rule for Rule03f {
-    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(3, 'F')
+    }
+
+    fn title(&self) -> &'static str {
+        "Do not put spaces between function names and argument lists"
+    }
+
+    fn is_fixable(&self) -> bool {
+        true
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Lint> {
         let mut diagnostics = Vec::new();
         let helper = QueryHelper::new(QUERY_STR, tree, code);
         let function_capture_i = helper.expect_index_for_capture("function");
@@ -58,12 +77,17 @@ impl Rule for Rule03f {
             let paren = helper.expect_node_for_capture_index(qmatch, paren_capture_i);
 
             if function.end_byte() != paren.start_byte() {
-                diagnostics.push(
+                let gap = function.end_byte()..paren.start_byte();
+                let mut lint = Lint::from(
                     Diagnostic::warning()
-                        .with_code("III:F")
                         .with_message("Expected no space between function and parenthesis")
-                        .with_label(Label::primary((), function.end_byte()..paren.start_byte())),
+                        .with_label(Label::primary((), gap.clone())),
                 );
+                // Only fix if there's nothing but whitespace (e.g. no comments) in between
+                if code[gap.clone()].trim().is_empty() {
+                    lint = lint.with_fix([Edit::delete(gap)]);
+                }
+                diagnostics.push(lint);
             }
         });
         diagnostics
@@ -79,9 +103,14 @@ mod tests {
 
     use indoc::indoc;
 
-    use crate::helpers::testing::test_captures;
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        helpers::testing::test_captures,
+        rules::api::{Edit, Rule, SourceInfo},
+    };
 
-    use super::QUERY_STR;
+    use super::{Rule03f, QUERY_STR};
 
     #[test]
     fn rule03f_captures() -> ExitCode {
@@ -113,4 +142,14 @@ mod tests {
         };
         test_captures(QUERY_STR, input)
     }
+
+    /// Tests that spaces are removed by the fix, but comments are not.
+    #[test]
+    fn fix() {
+        let code = "int main() {\n  printf (\"\");\n  printf /* c */ (\"\");\n}\n";
+        let lints = Rule03f {}.lint(&SourceInfo::new(code));
+        assert_eq!(2, lints.len());
+        assert_eq!(vec![Edit::delete(21..22)], lints[0].fix);
+        assert!(lints[1].fix.is_empty());
+    }
 }
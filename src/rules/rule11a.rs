@@ -17,53 +17,116 @@
 //! ```text
 //!    A. Do not use tabs for indentation.
 //! ```
+//!
+//! # Implementation notes
+//!
+//! The fix for a line which begins a statement, declaration, or comment replaces its indentation
+//! with the amount expected by [Rule II:A][crate::rules::rule02a] for the line's nesting depth, so
+//! that fixing one rule's warning doesn't produce another's. The indentation of other lines, e.g.
+//! ones continuing a wrapped statement, is replaced with spaces reaching the same column.
+
+use std::collections::HashMap;
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
+use serde::Deserialize;
+
+use crate::{
+    helpers::expand_tabs,
+    rules::{
+        api::{Edit, Lint, Rule},
+        rule02a::expected_indentation,
+    },
+};
 
-use crate::rules::api::Rule;
+use crate::rules::api::{RuleCode, SourceInfo};
 
-use crate::rules::api::SourceInfo;
+/// Parameters for [Rule XI:A][Rule11a].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Rule11aConfig {
+    /// Maximum number of diagnostics to output. If more than this are produced, a note is
+    /// displayed on the last one and the rest are hidden. Zero means there is no limit.
+    pub max_diagnostics: usize,
+}
+
+impl Default for Rule11aConfig {
+    fn default() -> Self {
+        Self { max_diagnostics: 3 }
+    }
+}
 
 /// # Rule XI:A.
 ///
 /// See module-level documentation for details.
 pub struct Rule11a {
     max_diagnostics: Option<usize>,
+    tab_width: usize,
+    indent_width: usize,
 }
 
 impl Rule11a {
     /// Constructs a new instance of this rule.
     ///
-    /// `max_diagnostics` specifies the maximum number of diagnostics to output. If more than this
-    /// are produced, a note is displayed on the last one and the rest are hidden.
+    /// `tab_width` specifies the distance between tab stops, in columns, and `indent_width` the
+    /// width of each level of indentation, as configured for Rule II:A. They are used when
+    /// replacing tabs with spaces.
     #[must_use]
-    pub fn new(max_diagnostics: Option<usize>) -> Self {
-        Self { max_diagnostics }
+    pub fn new(config: Rule11aConfig, tab_width: usize, indent_width: usize) -> Self {
+        Self {
+            max_diagnostics: (config.max_diagnostics != 0).then_some(config.max_diagnostics),
+            tab_width,
+            indent_width,
+        }
     }
 }
 
 impl Rule for Rule11a {
-    fn check(&self, SourceInfo { lines, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
-        let mut diagnostics = Vec::new();
+    fn code(&self) -> RuleCode {
+        RuleCode::new(11, 'A')
+    }
+
+    fn title(&self) -> &'static str {
+        "Do not use tabs for indentation"
+    }
+
+    fn is_fixable(&self) -> bool {
+        true
+    }
 
-        for &(line, start_pos) in lines {
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { tree, lines, .. }: &SourceInfo) -> Vec<Lint> {
+        let mut diagnostics: Vec<Lint> = Vec::new();
+        let expected: HashMap<usize, usize> =
+            expected_indentation(tree, lines, self.indent_width).into_iter().collect();
+
+        for (row, &(line, start_pos)) in lines.iter().enumerate() {
             // Get just the part of the line which consists of indentation
             let indentation = &line[..(line.len() - line.trim_start().len())];
             if indentation.is_empty() {
                 continue;
             }
+            let replacement = match expected.get(&row) {
+                Some(&width) => " ".repeat(width),
+                None => expand_tabs(indentation, self.tab_width),
+            };
+            let fix = Edit::replace(start_pos..(start_pos + indentation.len()), replacement);
 
             if indentation.as_bytes().iter().all(|c| *c == b'\t') {
                 // If the whole indentation string consists of tabs, then just label the whole
                 // thing.
                 diagnostics.push(
-                    Diagnostic::warning()
-                        .with_code("XI:A")
-                        .with_message("Use spaces instead of tabs for indentation")
-                        .with_label(
-                            Label::primary((), start_pos..(start_pos + indentation.len()))
-                                .with_message("Indentation uses tabs"),
-                        ),
+                    Lint::from(
+                        Diagnostic::warning()
+                            .with_message("Use spaces instead of tabs for indentation")
+                            .with_label(
+                                Label::primary((), start_pos..(start_pos + indentation.len()))
+                                    .with_message("Indentation uses tabs"),
+                            ),
+                    )
+                    .with_fix([fix]),
                 );
             } else {
                 // If there is a mix of tabs and non-tabs, label each tab separately
@@ -80,11 +143,13 @@ impl Rule for Rule11a {
                     .peekable();
                 if labels.peek().is_some() {
                     diagnostics.push(
-                        Diagnostic::warning()
-                            .with_code("XI:A")
-                            .with_message("Use spaces instead of tabs for indentation")
-                            .with_notes(vec!["Line mixes spaces and tabs".to_string()])
-                            .with_labels_iter(labels),
+                        Lint::from(
+                            Diagnostic::warning()
+                                .with_message("Use spaces instead of tabs for indentation")
+                                .with_notes(vec!["Line mixes spaces and tabs".to_string()])
+                                .with_labels_iter(labels),
+                        )
+                        .with_fix([fix]),
                     );
                 }
             }
@@ -94,10 +159,14 @@ impl Rule for Rule11a {
         if let Some(max) = self.max_diagnostics {
             if diagnostics.len() >= max {
                 let remaining = diagnostics.len() - max;
-                diagnostics.truncate(max);
-                diagnostics.last_mut().unwrap().notes.push(format!(
+                // The last diagnostic's fix also fixes the suppressed lines
+                let remaining_edits: Vec<Edit> =
+                    diagnostics.drain(max..).flat_map(|lint| lint.fix).collect();
+                let last = diagnostics.last_mut().unwrap();
+                last.diagnostic.notes.push(format!(
                     "{remaining} more lines contain tabs, but those warnings are suppressed to avoid noise."
                 ));
+                last.fix.extend(remaining_edits);
             }
         }
 
@@ -112,13 +181,15 @@ mod tests {
 
     use pretty_assertions::assert_eq;
 
-    use crate::rules::api::{Rule, SourceInfo};
+    use crate::rules::api::{Edit, Rule, SourceInfo};
+
+    use super::Rule11aConfig;
 
     /// Tests when lines contain only tabs for indentation.
     #[test]
     fn all_tabs() {
         let code = "#include <stdio.h>\nint main() {\n\t\tprintf(\"Hello, world!\\n\");\n\t\treturn 0;\n}\n";
-        let rule = super::Rule11a::new(None);
+        let rule = super::Rule11a::new(Rule11aConfig { max_diagnostics: 0 }, 8, 2);
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert_eq!(2, diagnostics.len());
         assert!(diagnostics.iter().all(|diag| diag.labels.len() == 1));
@@ -128,7 +199,7 @@ mod tests {
     #[test]
     fn mix_tabs_spaces() {
         let code = "#include <stdio.h>\nint main() {\n  \tprintf(\"Hello, world!\\n\");\n  \treturn 0;\n}\n";
-        let rule = super::Rule11a::new(None);
+        let rule = super::Rule11a::new(Rule11aConfig { max_diagnostics: 0 }, 8, 2);
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert_eq!(2, diagnostics.len());
         assert!(diagnostics.iter().all(|diag| diag.labels.len() == 1));
@@ -140,8 +211,47 @@ mod tests {
     fn no_tabs() {
         let code =
             "#include <stdio.h>\nint main() {\n  printf(\"Hello, world!\\n\");\n  return 0;\n}\n";
-        let rule = super::Rule11a::new(None);
+        let rule = super::Rule11a::new(Rule11aConfig { max_diagnostics: 0 }, 8, 2);
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert!(diagnostics.is_empty());
     }
+
+    /// Tests that the fix for the last diagnostic shown also fixes the suppressed lines.
+    #[test]
+    fn limit_fix() {
+        let code = "int main() {\n\tint x;\n  \tint y;\n\treturn 0;\n}\n";
+        let rule = super::Rule11a::new(Rule11aConfig { max_diagnostics: 2 }, 8, 2);
+        let lints = rule.lint(&SourceInfo::new(code));
+        assert_eq!(2, lints.len());
+        assert_eq!(vec![Edit::replace(13..14, " ".repeat(2))], lints[0].fix);
+        assert_eq!(
+            vec![
+                Edit::replace(21..24, " ".repeat(2)),
+                Edit::replace(31..32, " ".repeat(2))
+            ],
+            lints[1].fix
+        );
+    }
+
+    /// Tests that lines beginning statements are re-indented based on their nesting depth, and
+    /// that other lines keep their alignment.
+    #[test]
+    fn fix_indentation() {
+        let code = "int main() {\n\tif (x) {\n\t\tf(1,\n\t\t  2);\n\t}\n}\n";
+        let rule = super::Rule11a::new(Rule11aConfig { max_diagnostics: 0 }, 8, 2);
+        let fixes: Vec<Edit> = rule
+            .lint(&SourceInfo::new(code))
+            .into_iter()
+            .flat_map(|lint| lint.fix)
+            .collect();
+        assert_eq!(
+            vec![
+                Edit::replace(13..14, " ".repeat(2)),
+                Edit::replace(23..25, " ".repeat(4)),
+                Edit::replace(30..34, " ".repeat(18)),
+                Edit::replace(38..39, " ".repeat(2)),
+            ],
+            fixes
+        );
+    }
 }
@@ -21,10 +21,26 @@
 use std::num::NonZeroUsize;
 
 use codespan_reporting::diagnostic::{Diagnostic, Label};
+use serde::Deserialize;
 
-use crate::rules::api::Rule;
+use crate::rules::api::{Edit, Lint, Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Parameters for [Rule XI:B][Rule11b].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Rule11bConfig {
+    /// Maximum number of diagnostics to output. If more than this are produced, a note is
+    /// displayed on the last one and the rest are hidden. Zero means there is no limit.
+    pub max_diagnostics: usize,
+}
+
+impl Default for Rule11bConfig {
+    fn default() -> Self {
+        Self { max_diagnostics: 3 }
+    }
+}
 
 /// # Rule XI:B.
 ///
@@ -35,18 +51,33 @@ pub struct Rule11b {
 
 impl Rule11b {
     /// Constructs a new instance of this rule.
-    ///
-    /// `max_diagnostics` specifies the maximum number of diagnostics to output. If more than this
-    /// are produced, a note is displayed on the last one and the rest are hidden.
     #[must_use]
-    pub fn new(max_diagnostics: Option<NonZeroUsize>) -> Self {
-        Self { max_diagnostics }
+    pub fn new(config: Rule11bConfig) -> Self {
+        Self {
+            max_diagnostics: NonZeroUsize::new(config.max_diagnostics),
+        }
     }
 }
 
 impl Rule for Rule11b {
-    fn check(&self, SourceInfo { code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
-        let mut diagnostics = Vec::new();
+    fn code(&self) -> RuleCode {
+        RuleCode::new(11, 'B')
+    }
+
+    fn title(&self) -> &'static str {
+        "Use only UNIX newlines"
+    }
+
+    fn is_fixable(&self) -> bool {
+        true
+    }
+
+    fn check(&self, source: &SourceInfo) -> Vec<Diagnostic<()>> {
+        self.lint(source).into_iter().map(|lint| lint.diagnostic).collect()
+    }
+
+    fn lint(&self, SourceInfo { code, .. }: &SourceInfo) -> Vec<Lint> {
+        let mut diagnostics: Vec<Lint> = Vec::new();
 
         // Search for DOS-style newlines
         // Split on newlines, keeping track of the position within the source
@@ -58,31 +89,36 @@ impl Rule for Rule11b {
                 next_line_start_pos += line.len() + 1;
                 (line, cur_line_start_pos)
             })
-            .filter(|(line, _pos)| line.ends_with('\r'));
+            .filter(|(line, _pos)| line.ends_with('\r'))
+            // Position of '\r' in line
+            .map(|(line, start_pos)| start_pos + line.len() - 1);
 
         // Produce diagnostics
-        for (line, start_pos) in dos_lines.by_ref() {
-            // Position of '\r' in line
-            let cr_pos = start_pos + line.len() - 1;
+        #[allow(clippy::range_plus_one)]
+        for cr_pos in dos_lines.by_ref() {
             diagnostics.push(
-                Diagnostic::warning()
-                    .with_code("XI:B")
-                    .with_message("Line contains DOS-style ending")
-                    .with_label(
-                        #[allow(clippy::range_plus_one)]
-                        Label::primary((), cr_pos..(cr_pos + 1)),
-                    )
-                    .with_note("Use the `fileformat' option in Vim to fix this"),
+                Lint::from(
+                    Diagnostic::warning()
+                        .with_message("Line contains DOS-style ending")
+                        .with_label(Label::primary((), cr_pos..(cr_pos + 1)))
+                        .with_note("Use the `fileformat' option in Vim to fix this"),
+                )
+                .with_fix([Edit::delete(cr_pos..(cr_pos + 1))]),
             );
 
             // Apply the limit on the number of diagnostics produced
             if self.max_diagnostics.is_some_and(|max| diagnostics.len() == max.get()) {
                 // SAFETY: We know diagnostics will have a last element because if
                 // self.max_diagnostics is some, its value cannot be zero.
-                diagnostics.last_mut().unwrap().notes.push(format!(
+                let last = diagnostics.last_mut().unwrap();
+                // The last diagnostic's fix also fixes the suppressed lines
+                let remaining_edits: Vec<Edit> =
+                    dos_lines.map(|cr_pos| Edit::delete(cr_pos..(cr_pos + 1))).collect();
+                last.diagnostic.notes.push(format!(
                     "{} more lines contain DOS endings, but those warnings are suppressed to avoid noise.",
-                    dos_lines.count()
+                    remaining_edits.len()
                 ));
+                last.fix.extend(remaining_edits);
                 break;
             }
         }
@@ -93,11 +129,11 @@ impl Rule for Rule11b {
 
 #[cfg(test)]
 mod tests {
-    use std::num::NonZeroUsize;
-
     use pretty_assertions::{assert_eq, assert_str_eq};
 
-    use crate::rules::api::{Rule, SourceInfo};
+    use crate::rules::api::{Edit, Rule, SourceInfo};
+
+    use super::Rule11bConfig;
 
     /// Tests the diagnostics produced when a file has CRLF endings.
     /// Specifically checks for:
@@ -107,7 +143,7 @@ mod tests {
     #[test]
     fn has_crlf() {
         let code = "int main() {\r\n  return 0;\r\n}\r\n";
-        let rule = super::Rule11b::new(None);
+        let rule = super::Rule11b::new(Rule11bConfig { max_diagnostics: 0 });
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert_eq!(3, diagnostics.len());
         let cr_positions: Vec<usize> = code
@@ -125,7 +161,7 @@ mod tests {
     #[test]
     fn no_crlf() {
         let code = "int main() {\n  return 0;\n}\n";
-        let rule = super::Rule11b::new(None);
+        let rule = super::Rule11b::new(Rule11bConfig { max_diagnostics: 0 });
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert!(diagnostics.is_empty());
     }
@@ -134,11 +170,28 @@ mod tests {
     #[test]
     fn limit() {
         let code = "int main() {\r\n  return 0;\r\n}\r\n";
-        let rule = super::Rule11b::new(Some(NonZeroUsize::new(1).unwrap()));
+        let rule = super::Rule11b::new(Rule11bConfig { max_diagnostics: 1 });
         let diagnostics = rule.check(&SourceInfo::new(code));
         assert_eq!(1, diagnostics.len());
         assert_eq!(2, diagnostics[0].notes.len());
         // First note is Vim tip; second is remaining warnings.
         assert_str_eq!("2", diagnostics[0].notes[1].split_whitespace().next().unwrap());
     }
+
+    /// Tests that the fix for the last diagnostic shown also fixes the suppressed lines.
+    #[test]
+    fn limit_fix() {
+        let code = "int main() {\r\n  return 0;\r\n}\r\n";
+        let rule = super::Rule11b::new(Rule11bConfig { max_diagnostics: 1 });
+        let lints = rule.lint(&SourceInfo::new(code));
+        assert_eq!(1, lints.len());
+        assert_eq!(
+            vec![
+                Edit::delete(12..13),
+                Edit::delete(25..26),
+                Edit::delete(28..29)
+            ],
+            lints[0].fix
+        );
+    }
 }
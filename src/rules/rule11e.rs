@@ -23,7 +23,7 @@ use indoc::indoc;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// Tree-sitter query for Rule XI:E.
 const QUERY_STR: &str = indoc! {
@@ -39,6 +39,14 @@ const QUERY_STR: &str = indoc! {
 pub struct Rule11e {}
 
 impl Rule for Rule11e {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(11, 'E')
+    }
+
+    fn title(&self) -> &'static str {
+        "Do not use goto"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let mut diagnostics = Vec::new();
         let helper = QueryHelper::new(QUERY_STR, tree, code);
@@ -46,7 +54,6 @@ impl Rule for Rule11e {
             assert_eq!("goto", label);
             diagnostics.push(
                 Diagnostic::warning()
-                    .with_code("XI:E")
                     .with_message("Do not use `goto'")
                     .with_label(Label::primary((), capture.node.byte_range())),
             );
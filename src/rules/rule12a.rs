@@ -35,7 +35,7 @@ use tree_sitter::Node;
 
 use crate::{helpers::QueryHelper, rules::api::Rule};
 
-use crate::rules::api::SourceInfo;
+use crate::rules::api::{RuleCode, SourceInfo};
 
 /// # Rule XII:A.
 ///
@@ -64,6 +64,14 @@ const QUERY_STR: &str = indoc! {
 };
 
 impl Rule for Rule12a {
+    fn code(&self) -> RuleCode {
+        RuleCode::new(12, 'A')
+    }
+
+    fn title(&self) -> &'static str {
+        "Declare at most one variable per line"
+    }
+
     fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
         let mut diagnostics = Vec::new();
 
@@ -91,7 +99,6 @@ impl Rule for Rule12a {
                         let first_declarator = declarators.by_ref().next().unwrap();
                         diagnostics.push(
                             Diagnostic::warning()
-                                .with_code("XII:A")
                                 .with_message(
                                     "No more than one variable may be defined on a single line.",
                                 )
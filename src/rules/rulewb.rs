@@ -0,0 +1,405 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Rule W:B
+//!
+//! Function header comments are required by the CS 240 code standard, but Westwood doesn't have the
+//! standard's text for this requirement. Rather than guess at its section and letter, the rule uses
+//! a Westwood code, and the text below summarizes the requirement in Westwood's own words.
+//!
+//! ```text
+//!    B. Precede every function definition with a header comment
+//!       describing what the function does, its parameters, and the
+//!       value it returns.
+//!
+//!       This text is Westwood's summary, not a quote from the code
+//!       standard.
+//!
+//!       Example: /*
+//!                 * Returns the area of a circle with the given radius.
+//!                 */
+//!
+//!                double circle_area(double radius) {
+//! ```
+//!
+//! # Implementation notes
+//!
+//! A function's header comment is the comment (or run of consecutive comments) directly before
+//! its definition, separated from it only by whitespace. A comment which follows other code on the
+//! same line is not a header comment.
+//!
+//! Whether the comment actually describes the function can't be checked, but the rule can
+//! optionally require that the comment mentions the function's name (`require-name`), the name of
+//! each of its parameters (`require-params`), and, for functions which return a value, a word
+//! starting with "return" (`require-return`). These checks are case-insensitive.
+//!
+//! Tree-sitter can't tell a definition generated by a macro, like `DECL(name) {`, from an
+//! old-style definition relying on implicit `int`, like `main(c) {`, so the name of such a
+//! function is unknown. These definitions are only checked for a header comment, and the
+//! diagnostic doesn't name the function.
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indoc::indoc;
+use serde::Deserialize;
+use tree_sitter::{Node, QueryCapture};
+
+use crate::{
+    helpers::{declarator_path, function_definition_name, QueryHelper},
+    rules::api::Rule,
+};
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Tree-sitter query for Rule W:B.
+const QUERY_STR: &str = indoc! {
+    /* query */
+    r"
+    (function_definition) @function
+    "
+};
+
+/// Parameters for [Rule W:B][RuleWb].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleWbConfig {
+    /// Require the header comment to mention the function's name
+    pub require_name: bool,
+    /// Require the header comment to mention each parameter's name
+    pub require_params: bool,
+    /// Require the header comment to describe the return value of non-void functions
+    pub require_return: bool,
+}
+
+/// # Rule W:B.
+///
+/// See module-level documentation for details.
+pub struct RuleWb {
+    config: RuleWbConfig,
+}
+
+impl RuleWb {
+    /// Constructs a new instance of this rule.
+    #[must_use]
+    pub fn new(config: RuleWbConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Rule for RuleWb {
+    fn code(&self) -> RuleCode {
+        RuleCode::westwood('B')
+    }
+
+    fn title(&self) -> &'static str {
+        "Precede every function with a header comment"
+    }
+
+    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+        let helper = QueryHelper::new(QUERY_STR, tree, code);
+        let mut diagnostics = Vec::new();
+        helper.for_each_capture(|label: &str, capture: QueryCapture| match label {
+            "function" => {
+                let function = capture.node;
+                let name = function_definition_name(function, code);
+                let path = declarator_path(function).unwrap_or_default();
+                let declarator =
+                    path.iter().rev().find(|node| node.kind() == "function_declarator").copied();
+                let comments = header_comments(function);
+                let (Some(first), Some(last)) = (comments.first(), comments.last()) else {
+                    // Definitions we can't make sense of are labeled up to the start of the body
+                    let range = match declarator {
+                        Some(declarator) => declarator.byte_range(),
+                        None => {
+                            let end = function.child_by_field_name("declarator").map_or_else(
+                                || function.end_byte(),
+                                |declarator| declarator.end_byte(),
+                            );
+                            function.start_byte()..end
+                        }
+                    };
+                    let label = match name {
+                        Some(name) => format!("Function `{name}()' has no header comment"),
+                        None => "Function has no header comment".to_owned(),
+                    };
+                    diagnostics.push(
+                        Diagnostic::warning()
+                            .with_message("Functions must be preceded by a header comment")
+                            .with_label(Label::primary((), range).with_message(label)),
+                    );
+                    return;
+                };
+                // Skip checking the contents of the comment for definitions we can't make sense
+                // of, such as ones generated by macros
+                let (Some(name), Some(declarator)) = (name, declarator) else {
+                    return;
+                };
+
+                let comment_range = first.start_byte()..last.end_byte();
+                let comment = &code[comment_range.clone()];
+                let mut missing: Vec<String> = Vec::new();
+                if self.config.require_name && !mentions(comment, name) {
+                    missing.push("does not mention the function's name".to_owned());
+                }
+                if self.config.require_params {
+                    for param in parameter_names(declarator, code) {
+                        if !mentions(comment, param) {
+                            missing.push(format!("does not describe parameter `{param}'"));
+                        }
+                    }
+                }
+                if self.config.require_return
+                    && returns_value(function, &path, code)
+                    && !comment
+                        .split(|c: char| !c.is_alphanumeric())
+                        .any(|word| word.to_lowercase().starts_with("return"))
+                {
+                    missing.push("does not describe the return value".to_owned());
+                }
+                for problem in missing {
+                    diagnostics.push(
+                        Diagnostic::warning()
+                            .with_message(format!(
+                                "Header comment of function `{name}()' {problem}"
+                            ))
+                            .with_label(Label::primary((), comment_range.clone()))
+                            .with_label(
+                                Label::secondary((), declarator.byte_range())
+                                    .with_message("Function defined here"),
+                            ),
+                    );
+                }
+            }
+            _ => unreachable!(),
+        });
+        diagnostics
+    }
+}
+
+/// Returns the comments forming the header comment of the given function definition, in order.
+/// Returns an empty [`Vec`] if there is no header comment.
+fn header_comments(function: Node) -> Vec<Node> {
+    let mut comments = Vec::new();
+    let mut node = function;
+    while let Some(prev) = node.prev_sibling() {
+        if prev.kind() != "comment" {
+            break;
+        }
+        // Skip a comment which follows other code on the same line
+        if prev
+            .prev_sibling()
+            .is_some_and(|before| before.end_position().row == prev.start_position().row)
+        {
+            break;
+        }
+        comments.push(prev);
+        node = prev;
+    }
+    comments.reverse();
+    comments
+}
+
+/// Returns the names of the parameters declared by a `function_declarator` node. Unnamed
+/// parameters are skipped.
+fn parameter_names<'code>(declarator: Node, code: &'code str) -> Vec<&'code str> {
+    let Some(parameters) = declarator.child_by_field_name("parameters") else {
+        return Vec::new();
+    };
+    let mut cursor = parameters.walk();
+    parameters
+        .named_children(&mut cursor)
+        .filter(|param| param.kind() == "parameter_declaration")
+        .filter_map(|param| Some(&code[declarator_path(param)?.last()?.byte_range()]))
+        .collect()
+}
+
+/// Returns `true` if the function defined by the given `function_definition` node returns a
+/// value, i.e. its return type is not `void`. `path` is the function's [declarator
+/// path][crate::helpers::declarator_path].
+fn returns_value(function: Node, path: &[Node], code: &str) -> bool {
+    let is_void = function
+        .child_by_field_name("type")
+        .is_some_and(|ty| &code[ty.byte_range()] == "void");
+    // Return types like `void *` are represented by declarators around the function's declarator
+    let inner = path.iter().rposition(|node| node.kind() == "function_declarator");
+    let is_derived = path[..inner.unwrap_or(0)]
+        .iter()
+        .any(|node| node.kind() != "parenthesized_declarator");
+    !is_void || is_derived
+}
+
+/// Returns `true` if `text` contains `word` as a whole word, ignoring case.
+fn mentions(text: &str, word: &str) -> bool {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|candidate| candidate.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Rule, SourceInfo};
+
+    use super::{RuleWb, RuleWbConfig};
+
+    #[test]
+    fn missing_header_comment() {
+        let code = indoc! {"
+            #include <stdio.h>
+
+            /*
+             * Prints a greeting.
+             */
+
+            void greet(void) {
+              printf(\"hello\\n\");
+            }
+
+            // Returns 0.
+            // Multiple line comments form one header comment.
+            int main() {
+              return 0;
+            }
+
+            int x; /* Not a header comment */
+            static int *helper(int a) {
+              return 0;
+            }
+        "};
+        let rule = RuleWb::new(RuleWbConfig::default());
+        let source = SourceInfo::new(code);
+        let start = code.find("helper(").unwrap();
+        let end = code[start..].find(')').unwrap() + start + 1;
+        assert_eq!(
+            vec![Diagnostic::warning()
+                .with_message("Functions must be preceded by a header comment")
+                .with_label(
+                    Label::primary((), start..end)
+                        .with_message("Function `helper()' has no header comment")
+                )],
+            rule.check(&source)
+        );
+    }
+
+    #[test]
+    fn header_comment_contents() {
+        let code = indoc! {"
+            /* Adds two numbers. */
+            int add(int a, int *b) {
+              return a + *b;
+            }
+
+            /*
+             * scale(): Multiplies the vector by FACTOR.
+             * Returns the scaled vector.
+             */
+            struct vec *scale(struct vec *v, int factor) {
+              return v;
+            }
+
+            /* clear(): Clears arr, which has length len. */
+            void clear(int arr[], size_t len, ...) {
+            }
+        "};
+        let rule = RuleWb::new(RuleWbConfig {
+            require_name: true,
+            require_params: true,
+            require_return: true,
+        });
+        let source = SourceInfo::new(code);
+        let messages: Vec<String> =
+            rule.check(&source).into_iter().map(|diagnostic| diagnostic.message).collect();
+        assert_eq!(
+            vec![
+                "Header comment of function `add()' does not mention the function's name",
+                "Header comment of function `add()' does not describe parameter `a'",
+                "Header comment of function `add()' does not describe parameter `b'",
+                "Header comment of function `add()' does not describe the return value",
+                "Header comment of function `scale()' does not describe parameter `v'",
+            ],
+            messages
+        );
+    }
+
+    #[test]
+    fn unusual_declarators() {
+        let code = indoc! {"
+            /* f */
+            int (f)(void) {
+              return 0;
+            }
+
+            /* h */
+            int (*h(void))(int) {
+              return 0;
+            }
+
+            /* k: p */
+            void (k)(int (*p)) {
+            }
+        "};
+        let rule = RuleWb::new(RuleWbConfig {
+            require_name: true,
+            require_params: true,
+            require_return: true,
+        });
+        let source = SourceInfo::new(code);
+        let messages: Vec<String> =
+            rule.check(&source).into_iter().map(|diagnostic| diagnostic.message).collect();
+        assert_eq!(
+            vec![
+                "Header comment of function `f()' does not describe the return value",
+                "Header comment of function `h()' does not describe the return value",
+            ],
+            messages
+        );
+    }
+
+    /// Tests definitions whose names can't be determined, which tree-sitter parses alike.
+    #[test]
+    fn unknown_names() {
+        let code = indoc! {"
+            #define DECL(x) int x(void)
+            DECL(g) {
+              return 1;
+            }
+
+            main(c) {
+              return c;
+            }
+
+            /* Content checks are skipped */
+            DECL(h) {
+              return 1;
+            }
+        "};
+        let rule = RuleWb::new(RuleWbConfig {
+            require_name: true,
+            require_params: true,
+            require_return: true,
+        });
+        let source = SourceInfo::new(code);
+        let diagnostic = |name: &str| {
+            let start = code.find(name).unwrap();
+            Diagnostic::warning()
+                .with_message("Functions must be preceded by a header comment")
+                .with_label(
+                    Label::primary((), start..start + name.len())
+                        .with_message("Function has no header comment"),
+                )
+        };
+        assert_eq!(vec![diagnostic("DECL(g)"), diagnostic("main(c)")], rule.check(&source));
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Rule W:C
+//!
+//! Submissions are expected to begin with a header comment, which graders otherwise check by hand.
+//! This requirement isn't one of the rules of the code standard quoted by Westwood, so the rule
+//! uses a Westwood code, and the text below is a summary rather than a quote.
+//!
+//! ```text
+//!    C. Begin every file with a header comment, placed before any
+//!       #include lines, which identifies the file, its author, and
+//!       its purpose.
+//!
+//!       This text is Westwood's summary, not a quote from the code
+//!       standard.
+//!
+//!       Example: /*
+//!                 * Name: hw5.c
+//!                 * Author: Purdue Pete
+//!                 *
+//!                 * Computes statistics about a list of grades.
+//!                 */
+//!
+//!                #include <stdio.h>
+//! ```
+//!
+//! # Implementation notes
+//!
+//! The header comment is the comment (or run of consecutive comments) at the very start of the
+//! file. If anything other than a comment comes first, the file has no header comment.
+//!
+//! What the header comment must contain differs between assignments, so the `required-fields`
+//! option gives a list of [regular expressions][regex] which must each match somewhere in the
+//! header comment. For example, `["Name:", "(?i)author:"]` requires a `Name:` field and an
+//! `Author:` field in any case. By default, no fields are required.
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use regex::Regex;
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+use crate::rules::api::Rule;
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Parameters for [Rule W:C][RuleWc].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleWcConfig {
+    /// Regular expressions which must each match part of the header comment
+    #[serde(deserialize_with = "deserialize_patterns")]
+    pub required_fields: Vec<String>,
+}
+
+/// Deserializes a list of regular expressions, producing an error if any of them is invalid.
+fn deserialize_patterns<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    let patterns = Vec::<String>::deserialize(deserializer)?;
+    for pattern in &patterns {
+        Regex::new(pattern).map_err(D::Error::custom)?;
+    }
+    Ok(patterns)
+}
+
+/// # Rule W:C.
+///
+/// See module-level documentation for details.
+pub struct RuleWc {
+    /// Source and compiled form of each required field pattern.
+    required_fields: Vec<(String, Regex)>,
+}
+
+impl RuleWc {
+    /// Constructs a new instance of this rule.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the required field patterns is not a valid regular expression. Patterns
+    /// are validated when the configuration is deserialized.
+    #[must_use]
+    pub fn new(config: RuleWcConfig) -> Self {
+        let required_fields = config
+            .required_fields
+            .into_iter()
+            .map(|pattern| {
+                let regex = Regex::new(&pattern).expect("Expected a valid regular expression");
+                (pattern, regex)
+            })
+            .collect();
+        Self { required_fields }
+    }
+}
+
+impl Rule for RuleWc {
+    fn code(&self) -> RuleCode {
+        RuleCode::westwood('C')
+    }
+
+    fn title(&self) -> &'static str {
+        "Begin every file with a header comment"
+    }
+
+    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let mut children = root.children(&mut cursor).peekable();
+        let Some(first) = children.peek().copied() else {
+            return Vec::new();
+        };
+        let mut comments = Vec::new();
+        while let Some(comment) = children.next_if(|node| node.kind() == "comment") {
+            comments.push(comment);
+        }
+
+        let (Some(first_comment), Some(last_comment)) = (comments.first(), comments.last()) else {
+            // Only mark the first line of whatever comes first, since it may be a whole function
+            let start = first.start_byte();
+            let end =
+                code[start..first.end_byte()].find('\n').map_or(first.end_byte(), |i| start + i);
+            let message = if first.kind() == "preproc_include" {
+                "`#include' occurs before the header comment"
+            } else {
+                "Expected a header comment before this"
+            };
+            return vec![Diagnostic::warning()
+                .with_message("Files must begin with a header comment")
+                .with_label(Label::primary((), start..end).with_message(message))];
+        };
+
+        let comment_range = first_comment.start_byte()..last_comment.end_byte();
+        let comment = &code[comment_range.clone()];
+        self.required_fields
+            .iter()
+            .filter(|(_, regex)| !regex.is_match(comment))
+            .map(|(pattern, _)| {
+                Diagnostic::warning()
+                    .with_message(format!("Header comment is missing a field matching `{pattern}'"))
+                    .with_label(Label::primary((), comment_range.clone()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Rule, SourceInfo};
+
+    use super::{RuleWc, RuleWcConfig};
+
+    #[test]
+    fn missing_header_comment() {
+        let rule = RuleWc::new(RuleWcConfig::default());
+        let cases = [
+            (
+                "#include <stdio.h>\n/* Too late */\n",
+                0..18,
+                "`#include' occurs before the header comment",
+            ),
+            ("int main() {\n  return 0;\n}\n", 0..12, "Expected a header comment before this"),
+        ];
+        for (code, range, message) in cases {
+            let source = SourceInfo::new(code);
+            assert_eq!(
+                vec![Diagnostic::warning()
+                    .with_message("Files must begin with a header comment")
+                    .with_label(Label::primary((), range).with_message(message))],
+                rule.check(&source),
+                "{code}"
+            );
+        }
+
+        for code in ["", "\n\n/* Header */\n#include <stdio.h>\n"] {
+            let source = SourceInfo::new(code);
+            assert_eq!(Vec::<Diagnostic<()>>::new(), rule.check(&source), "{code}");
+        }
+    }
+
+    #[test]
+    fn required_fields() {
+        let code = indoc! {"
+            // Name: hw5.c
+            // author: Purdue Pete
+
+            #include <stdio.h>
+        "};
+        let rule = RuleWc::new(RuleWcConfig {
+            required_fields: vec![
+                "Name:".to_owned(),
+                "Author:".to_owned(),
+                "(?i)author:".to_owned(),
+            ],
+        });
+        let source = SourceInfo::new(code);
+        assert_eq!(
+            vec![Diagnostic::warning()
+                .with_message("Header comment is missing a field matching `Author:'")
+                .with_label(Label::primary((), 0..37))],
+            rule.check(&source)
+        );
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        let result: Result<RuleWcConfig, _> = toml::from_str(r#"required-fields = ["("]"#);
+        assert!(result.is_err());
+    }
+}
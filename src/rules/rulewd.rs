@@ -0,0 +1,313 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Rule W:D
+//!
+//! Magic numbers are covered by [Rule I:C][crate::rules::rule01c], which says "Constants must be
+//! declared using #define". That code belongs to the rule checking the constants which are
+//! declared, though, and reporting every bare number in the code is much noisier, so this check is
+//! a separate rule with a Westwood code. That way it can be configured, disabled, and suppressed
+//! independently of I:C. The text below is a summary rather than a quote from the standard.
+//!
+//! ```text
+//!    D. Do not use numeric values (magic numbers) directly in code,
+//!       other than trivial ones such as 0 and 1. Declare a constant
+//!       for the value using #define instead.
+//!
+//!       This text is Westwood's summary, not a quote from the code
+//!       standard.
+//!
+//!       Example: #define BUFFER_SIZE (4096)
+//!
+//!                char buffer[BUFFER_SIZE];
+//! ```
+//!
+//! # Implementation notes
+//!
+//! Every numeric literal in the code is checked, except for
+//!  - the values of `#define`d constants, which is where the numbers belong,
+//!  - conditions of `#if`/`#elif` directives,
+//!  - values given to enumerators and bit-field widths, since these already name the value, and
+//!  - array sizes which use a constant defined earlier in the file, like `char name[NAME_LEN + 1]`.
+//!
+//! The `allowed` option lists the values which may appear anywhere, and defaults to `[0, 1, -1, 2]`.
+//! A negated literal such as `-1` is treated as a single negative number. Floating-point literals
+//! are allowed if their value is one of the allowed integers. Numbers in `case` labels are only
+//! allowed if the `allow-case-labels` option is set.
+
+use std::collections::HashSet;
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indoc::indoc;
+use serde::Deserialize;
+use tree_sitter::{Node, QueryCapture};
+
+use crate::{helpers::QueryHelper, rules::api::Rule};
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Tree-sitter query for Rule W:D.
+const QUERY_STR: &str = indoc! {
+    /* query */
+    r"
+    (preproc_def name: (identifier) @macro)
+    (preproc_function_def name: (identifier) @macro)
+    (number_literal) @number
+    "
+};
+
+/// Parameters for [Rule W:D][RuleWd].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleWdConfig {
+    /// Values which may be used anywhere
+    pub allowed: Vec<i32>,
+    /// Whether numbers may be used in `case` labels
+    pub allow_case_labels: bool,
+}
+
+impl Default for RuleWdConfig {
+    fn default() -> Self {
+        Self {
+            allowed: vec![0, 1, -1, 2],
+            allow_case_labels: false,
+        }
+    }
+}
+
+/// # Rule W:D.
+///
+/// See module-level documentation for details.
+pub struct RuleWd {
+    config: RuleWdConfig,
+}
+
+impl RuleWd {
+    /// Constructs a new instance of this rule.
+    #[must_use]
+    pub fn new(config: RuleWdConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns `true` if the literal with the given text is one of the allowed values.
+    fn is_allowed(&self, literal: &str, negated: bool) -> bool {
+        match parse_literal(literal) {
+            Some(Number::Integer(value)) => {
+                let value = if negated { -value } else { value };
+                i32::try_from(value).is_ok_and(|value| self.config.allowed.contains(&value))
+            }
+            Some(Number::Float(value)) => {
+                let value = if negated { -value } else { value };
+                #[allow(clippy::float_cmp)] // Comparing against exact integers
+                self.config.allowed.iter().any(|&allowed| f64::from(allowed) == value)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the given `number_literal` node is in a position where any number is
+    /// allowed. See the [module-level documentation][self] for the list of such positions.
+    fn is_exempt(&self, literal: Node, code: &str, macros: &HashSet<&str>) -> bool {
+        let mut child = literal;
+        while let Some(parent) = child.parent() {
+            let is_field = |field: &str| parent.child_by_field_name(field) == Some(child);
+            match parent.kind() {
+                "preproc_def" | "preproc_function_def" | "bitfield_clause" => return true,
+                "preproc_if" | "preproc_elif" if is_field("condition") => return true,
+                "enumerator" if is_field("value") => return true,
+                "case_statement" if is_field("value") => return self.config.allow_case_labels,
+                "array_declarator" if is_field("size") => {
+                    return references_macro(child, code, macros)
+                }
+                _ => {}
+            }
+            child = parent;
+        }
+        false
+    }
+}
+
+impl Rule for RuleWd {
+    fn code(&self) -> RuleCode {
+        RuleCode::westwood('D')
+    }
+
+    fn title(&self) -> &'static str {
+        "Use named constants instead of magic numbers"
+    }
+
+    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+        let helper = QueryHelper::new(QUERY_STR, tree, code);
+        let mut diagnostics = Vec::new();
+        // Names of the macros defined so far. Captures are in order, so when we check a number,
+        // this contains exactly the macros defined before it.
+        let mut macros: HashSet<&str> = HashSet::new();
+        helper.for_each_capture(|label: &str, capture: QueryCapture| match label {
+            "macro" => {
+                macros.insert(&code[capture.node.byte_range()]);
+            }
+            "number" => {
+                let literal = capture.node;
+                if self.is_exempt(literal, code, &macros) {
+                    return;
+                }
+                // Treat a negated literal as a single negative number
+                let (node, negated) = match literal.parent() {
+                    Some(parent)
+                        if parent.kind() == "unary_expression"
+                            && parent
+                                .child_by_field_name("operator")
+                                .is_some_and(|op| op.kind() == "-") =>
+                    {
+                        (parent, true)
+                    }
+                    _ => (literal, false),
+                };
+                if self.is_allowed(&code[literal.byte_range()], negated) {
+                    return;
+                }
+                let text = &code[node.byte_range()];
+                diagnostics.push(
+                    Diagnostic::warning()
+                        .with_message(format!("Magic number `{text}' should be a named constant"))
+                        .with_label(
+                            Label::primary((), node.byte_range())
+                                .with_message("Define a constant for this value using `#define'"),
+                        ),
+                );
+            }
+            _ => unreachable!(),
+        });
+        diagnostics
+    }
+}
+
+/// Value of a numeric literal.
+enum Number {
+    Integer(i128),
+    Float(f64),
+}
+
+/// Parses the text of a C numeric literal. Returns [`None`] if the literal is not valid or its
+/// value doesn't fit in the returned type.
+fn parse_literal(literal: &str) -> Option<Number> {
+    let literal = literal.replace('\'', "").to_ascii_lowercase();
+    let integer = |digits: &str, radix: u32| {
+        i128::from_str_radix(digits.trim_end_matches(['u', 'l']), radix)
+            .ok()
+            .map(Number::Integer)
+    };
+    if let Some(digits) = literal.strip_prefix("0x") {
+        // Hexadecimal floating-point literals are rare enough that we never allow them
+        if digits.contains(['.', 'p']) {
+            None
+        } else {
+            integer(digits, 16)
+        }
+    } else if let Some(digits) = literal.strip_prefix("0b") {
+        integer(digits, 2)
+    } else if literal.contains(['.', 'e']) {
+        literal.trim_end_matches(['f', 'l']).parse().ok().map(Number::Float)
+    } else {
+        // Strip the suffix first so that literals like `0u` aren't mistaken for octal
+        let digits = literal.trim_end_matches(['u', 'l']);
+        if digits.len() > 1 && digits.starts_with('0') {
+            integer(&digits[1..], 8)
+        } else {
+            integer(digits, 10)
+        }
+    }
+}
+
+/// Returns `true` if the given node contains an identifier which is one of the given macro names.
+fn references_macro(node: Node, code: &str, macros: &HashSet<&str>) -> bool {
+    if node.kind() == "identifier" {
+        return macros.contains(&code[node.byte_range()]);
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .any(|child| references_macro(child, code, macros));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Rule, SourceInfo};
+
+    use super::{RuleWd, RuleWdConfig};
+
+    #[test]
+    fn rulewd() {
+        let code = indoc! {"
+            #define NAME_LEN (32)
+            #if 0
+            #endif
+            enum color { RED = 4 };
+            struct flags { int a : 3; };
+            int main() {
+              char name[NAME_LEN + 1];
+              char buffer[4096];
+              double area = 3.14159 * 2 * r * r;
+              int x = -1 + 1.0 + 0x0 - 2UL;
+              switch (x) {
+                case 7:
+                  return -5;
+              }
+              x = 010;
+              x = 0u + 0L + 0UL;
+            }
+        "};
+        let source = SourceInfo::new(code);
+        let expected: Vec<Diagnostic<()>> = ["4096", "3.14159", "7", "-5", "010"]
+            .into_iter()
+            .map(|text| {
+                let start = code.find(&format!("{text}]")).or_else(|| code.find(text)).unwrap();
+                Diagnostic::warning()
+                    .with_message(format!("Magic number `{text}' should be a named constant"))
+                    .with_label(
+                        Label::primary((), start..(start + text.len()))
+                            .with_message("Define a constant for this value using `#define'"),
+                    )
+            })
+            .collect();
+        assert_eq!(expected, RuleWd::new(RuleWdConfig::default()).check(&source));
+
+        let rule = RuleWd::new(RuleWdConfig {
+            allowed: vec![8, -5],
+            allow_case_labels: true,
+        });
+        let messages: Vec<String> =
+            rule.check(&source).into_iter().map(|diagnostic| diagnostic.message).collect();
+        assert_eq!(
+            vec![
+                "Magic number `4096' should be a named constant",
+                "Magic number `3.14159' should be a named constant",
+                "Magic number `2' should be a named constant",
+                "Magic number `-1' should be a named constant",
+                "Magic number `1.0' should be a named constant",
+                "Magic number `0x0' should be a named constant",
+                "Magic number `2UL' should be a named constant",
+                "Magic number `0u' should be a named constant",
+                "Magic number `0L' should be a named constant",
+                "Magic number `0UL' should be a named constant",
+            ],
+            messages
+        );
+    }
+}
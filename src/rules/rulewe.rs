@@ -0,0 +1,342 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Rule W:E
+//!
+//! The course teaches that the results of library calls which can fail must be checked, but this
+//! isn't one of the rules of the code standard quoted by Westwood. The rule therefore uses a
+//! Westwood code, and the text below describes it in the style of the standard.
+//!
+//! ```text
+//!    E. Check the value returned by library functions which can
+//!       fail, such as fopen(), malloc(), fscanf(), and fread(),
+//!       before using their results.
+//!
+//!       This text is Westwood's summary, not a quote from the code
+//!       standard.
+//!
+//!       Example: fp = fopen(FILE_NAME, "r");
+//!                if (fp == NULL) {
+//!                  return FILE_READ_ERR;
+//!                }
+//! ```
+//!
+//! # Implementation notes
+//!
+//! The functions to check are given by the `functions` option. A call to one of them is reported
+//! if
+//!  - its value is discarded, i.e. the call is a statement by itself, or
+//!  - its value is assigned to a variable, either in a declaration or an assignment statement, and
+//!    the next use of that variable is not a check.
+//!
+//! A use of a variable is a check if the variable is compared using a comparison operator, negated
+//! using `!`, used as an operand of `&&` or `||`, or used by itself as the condition of an `if`
+//! statement, loop, or `?:` operator. Calls whose value is used in any other way, such as
+//! `if ((fp = fopen(...)) == NULL)`, are assumed to be checked. A value can be discarded on purpose
+//! by casting the call to `void`. Operands of `sizeof` are not evaluated, so they are not uses.
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indoc::indoc;
+use serde::Deserialize;
+use tree_sitter::{Node, QueryCapture};
+
+use crate::{helpers::QueryHelper, rules::api::Rule};
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Tree-sitter query for Rule W:E.
+const QUERY_STR: &str = indoc! {
+    /* query */
+    r"
+    (call_expression function: (identifier)) @call
+    "
+};
+
+/// Parameters for [Rule W:E][RuleWe].
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleWeConfig {
+    /// Names of the functions whose return values must be checked
+    pub functions: Vec<String>,
+}
+
+impl Default for RuleWeConfig {
+    fn default() -> Self {
+        let functions = [
+            "fopen", "freopen", "malloc", "calloc", "realloc", "fscanf", "scanf", "sscanf",
+            "fread", "fwrite", "fgets",
+        ];
+        Self {
+            functions: functions.into_iter().map(str::to_owned).collect(),
+        }
+    }
+}
+
+/// # Rule W:E.
+///
+/// See module-level documentation for details.
+pub struct RuleWe {
+    config: RuleWeConfig,
+}
+
+impl RuleWe {
+    /// Constructs a new instance of this rule.
+    #[must_use]
+    pub fn new(config: RuleWeConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Rule for RuleWe {
+    fn code(&self) -> RuleCode {
+        RuleCode::westwood('E')
+    }
+
+    fn title(&self) -> &'static str {
+        "Check the return values of library functions"
+    }
+
+    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+        let helper = QueryHelper::new(QUERY_STR, tree, code);
+        let mut diagnostics = Vec::new();
+        helper.for_each_capture(|label: &str, capture: QueryCapture| match label {
+            "call" => {
+                let call = capture.node;
+                let function = call
+                    .child_by_field_name("function")
+                    .expect("Expected call to have a `function' field");
+                let name = &code[function.byte_range()];
+                if !self.config.functions.iter().any(|function| function == name) {
+                    return;
+                }
+                let diagnostic = Diagnostic::warning()
+                    .with_message(format!("Return value of `{name}()' is not checked"));
+                let Some(parent) = call.parent() else {
+                    return;
+                };
+                if parent.kind() == "expression_statement" {
+                    diagnostics.push(
+                        diagnostic.with_label(
+                            Label::primary((), call.byte_range())
+                                .with_message("Return value is discarded"),
+                        ),
+                    );
+                    return;
+                }
+                let Some(variable) = assigned_variable(call) else {
+                    return;
+                };
+                let variable_name = &code[variable.byte_range()];
+                // Arguments of the call itself are evaluated before the assignment
+                match next_use(variable, call.end_byte(), code) {
+                    Some(usage) if is_check(usage) => {}
+                    Some(usage) => diagnostics.push(
+                        diagnostic
+                            .with_label(Label::primary((), call.byte_range()).with_message(
+                                format!("Return value is assigned to `{variable_name}' here"),
+                            ))
+                            .with_label(Label::secondary((), usage.byte_range()).with_message(
+                                format!("`{variable_name}' is used before being checked"),
+                            )),
+                    ),
+                    None => diagnostics.push(diagnostic.with_label(
+                        Label::primary((), call.byte_range()).with_message(format!(
+                            "Return value is assigned to `{variable_name}', which is never checked"
+                        )),
+                    )),
+                }
+            }
+            _ => unreachable!(),
+        });
+        diagnostics
+    }
+}
+
+/// If the value of the given `call_expression` node is stored in a variable by a declaration or
+/// an assignment statement, returns the identifier node of that variable.
+fn assigned_variable(call: Node) -> Option<Node> {
+    // Look through casts and parentheses, like in `(int *) malloc(...)`
+    let mut value = call;
+    let mut parent = call.parent()?;
+    while matches!(parent.kind(), "parenthesized_expression" | "cast_expression") {
+        value = parent;
+        parent = parent.parent()?;
+    }
+    let mut target = match parent.kind() {
+        "init_declarator" if parent.child_by_field_name("value") == Some(value) => {
+            parent.child_by_field_name("declarator")?
+        }
+        "assignment_expression"
+            if parent.child_by_field_name("right") == Some(value)
+                && parent.parent()?.kind() == "expression_statement" =>
+        {
+            parent.child_by_field_name("left")?
+        }
+        _ => return None,
+    };
+    // Find the name inside a declarator like `*fp`
+    while target.kind() == "pointer_declarator" {
+        target = target.child_by_field_name("declarator")?;
+    }
+    (target.kind() == "identifier").then_some(target)
+}
+
+/// Returns the first identifier node starting at or after the byte offset `after` which refers to
+/// the same name as the given one, within the enclosing function. Operands which are not
+/// evaluated, like that of `sizeof`, are skipped.
+fn next_use<'tree>(variable: Node<'tree>, after: usize, code: &str) -> Option<Node<'tree>> {
+    let mut scope = variable;
+    while scope.kind() != "function_definition" {
+        scope = scope.parent()?;
+    }
+    let name = &code[variable.byte_range()];
+    let mut cursor = scope.walk();
+    // Depth-first search, in order, for identifiers after the given one
+    loop {
+        let node = cursor.node();
+        if node.kind() == "identifier"
+            && node.start_byte() >= after
+            && &code[node.byte_range()] == name
+        {
+            return Some(node);
+        }
+        let is_evaluated = !matches!(node.kind(), "sizeof_expression" | "alignof_expression");
+        if is_evaluated && node.end_byte() > after && cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return None;
+            }
+        }
+    }
+}
+
+/// Returns `true` if the given identifier node is used in a way that checks its value.
+fn is_check(usage: Node) -> bool {
+    let mut node = usage;
+    let mut parent = usage.parent();
+    while let Some(p) = parent.filter(|p| p.kind() == "parenthesized_expression") {
+        node = p;
+        parent = p.parent();
+    }
+    let Some(parent) = parent else {
+        return false;
+    };
+    match parent.kind() {
+        "binary_expression" => parent.child_by_field_name("operator").is_some_and(|op| {
+            matches!(op.kind(), "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||")
+        }),
+        "unary_expression" => {
+            parent.child_by_field_name("operator").is_some_and(|op| op.kind() == "!")
+        }
+        "if_statement"
+        | "while_statement"
+        | "do_statement"
+        | "for_statement"
+        | "conditional_expression" => parent.child_by_field_name("condition") == Some(node),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Rule, SourceInfo};
+
+    use super::{RuleWe, RuleWeConfig};
+
+    #[test]
+    fn rulewe() {
+        let code = indoc! {r#"
+            int main() {
+              FILE *fp = fopen("a.txt", "r");
+              if (fp == NULL) {
+                return 1;
+              }
+              char *buf = malloc(10);
+              if (!buf) return 1;
+              int *p = NULL;
+              p = malloc(4);
+              *p = 3;
+              fscanf(fp, "%d", p);
+              (void) fscanf(fp, "%d", p);
+              while (fscanf(fp, "%d", p) == 1) {}
+              if ((fp = fopen("b.txt", "r")) == NULL) {}
+              int n = fread(buf, 1, 1, fp);
+              printf("hello");
+              char *unused = calloc(1, 1);
+              int *s = malloc(sizeof(*s));
+              if (s == NULL) return 1;
+              int *q = (int *) malloc(sizeof(int));
+              *q = 1;
+              p = realloc(p, 8);
+              if (p == NULL) return 1;
+              int m = 1;
+              m = fread(buf, 1, m, fp);
+              if (m != 1) return 1;
+            }
+        "#};
+        let source = SourceInfo::new(code);
+        let find = |text: &str| {
+            let start = code.find(text).unwrap();
+            start..(start + text.len())
+        };
+        let expected = vec![
+            Diagnostic::warning()
+                .with_message("Return value of `malloc()' is not checked")
+                .with_label(
+                    Label::primary((), find("malloc(4)"))
+                        .with_message("Return value is assigned to `p' here"),
+                )
+                .with_label(
+                    Label::secondary((), find("p = 3").start..find("p = 3").start + 1)
+                        .with_message("`p' is used before being checked"),
+                ),
+            Diagnostic::warning()
+                .with_message("Return value of `fscanf()' is not checked")
+                .with_label(
+                    Label::primary((), find(r#"fscanf(fp, "%d", p)"#))
+                        .with_message("Return value is discarded"),
+                ),
+            Diagnostic::warning()
+                .with_message("Return value of `fread()' is not checked")
+                .with_label(
+                    Label::primary((), find("fread(buf, 1, 1, fp)"))
+                        .with_message("Return value is assigned to `n', which is never checked"),
+                ),
+            Diagnostic::warning()
+                .with_message("Return value of `calloc()' is not checked")
+                .with_label(
+                    Label::primary((), find("calloc(1, 1)")).with_message(
+                        "Return value is assigned to `unused', which is never checked",
+                    ),
+                ),
+            Diagnostic::warning()
+                .with_message("Return value of `malloc()' is not checked")
+                .with_label(
+                    Label::primary((), find("malloc(sizeof(int))"))
+                        .with_message("Return value is assigned to `q' here"),
+                )
+                .with_label(
+                    Label::secondary((), find("q = 1").start..find("q = 1").start + 1)
+                        .with_message("`q' is used before being checked"),
+                ),
+        ];
+        assert_eq!(expected, RuleWe::new(RuleWeConfig::default()).check(&source));
+    }
+}
@@ -0,0 +1,729 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Rule W:F
+//!
+//! Leaked files cost points on the course's file I/O assignments, but closing files and freeing
+//! memory isn't one of the rules of the code standard quoted by Westwood. The rule therefore uses a
+//! Westwood code, and the text below describes it in the style of the standard.
+//!
+//! ```text
+//!    F. Close every file opened with fopen() using fclose() before
+//!       the function which opened it returns, unless the file pointer
+//!       is returned or stored for later use. Likewise, free memory
+//!       allocated with malloc() or calloc() using free().
+//!
+//!       This text is Westwood's summary, not a quote from the code
+//!       standard.
+//!
+//!       Example: fp = fopen(FILE_NAME, "r");
+//!                if (fp == NULL) {
+//!                  return FILE_READ_ERR;
+//!                }
+//!                ...
+//!                fclose(fp);
+//!                fp = NULL;
+//!                return OK;
+//! ```
+//!
+//! # Implementation notes
+//!
+//! Only values stored in local variables are tracked, i.e. the result of `fopen()` must be used
+//! to initialize a variable declared in the function, or be assigned to one. Files are always
+//! checked, and memory is checked if the `check-memory` option is set.
+//!
+//! Each function is walked from top to bottom, following every path through `if`, `switch`, and
+//! loop statements, to find the points at which the function can return (or reach its closing
+//! brace) while the file may still be open. A file is no longer open once
+//!  - the variable is passed to `fclose()`,
+//!  - the variable is returned or stored somewhere else, such as in a structure field, or
+//!  - the variable is assigned a different value.
+//!
+//! To avoid reporting an error after a failed `fopen()`, conditions which compare the variable to
+//! `NULL`, like `fp == NULL`, `!fp`, or `(fp = fopen(...)) != NULL`, are taken into account. Loops
+//! are only walked once, and paths which end with `goto`, `exit()`, or `abort()` are not checked.
+
+use std::ops::Range;
+
+use codespan_reporting::diagnostic::{Diagnostic, Label};
+use indoc::indoc;
+use serde::Deserialize;
+use tree_sitter::{Node, QueryCapture};
+
+use crate::{helpers::QueryHelper, rules::api::Rule};
+
+use crate::rules::api::{RuleCode, SourceInfo};
+
+/// Tree-sitter query for Rule W:F.
+const QUERY_STR: &str = indoc! {
+    /* query */
+    r"
+    (call_expression function: (identifier)) @call
+    "
+};
+
+/// Functions which end the program, and thus end a path without leaking anything.
+const EXIT_FUNCTIONS: [&str; 4] = ["exit", "abort", "_Exit", "quick_exit"];
+
+/// Parameters for [Rule W:F][RuleWf].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RuleWfConfig {
+    /// Whether to also check that memory allocated with `malloc()` or `calloc()` is freed
+    pub check_memory: bool,
+}
+
+/// Kind of resource which must be released.
+struct Resource {
+    /// Functions which acquire the resource.
+    acquire: &'static [&'static str],
+    /// Function which releases the resource.
+    release: &'static str,
+    /// Description of the resource, used in diagnostic messages.
+    description: &'static str,
+    /// Past participle of the acquiring action.
+    acquired: &'static str,
+}
+
+/// Files, which are always checked.
+const FILE: Resource = Resource {
+    acquire: &["fopen"],
+    release: "fclose",
+    description: "File",
+    acquired: "opened",
+};
+
+/// Memory, which is checked if [`RuleWfConfig::check_memory`] is set.
+const MEMORY: Resource = Resource {
+    acquire: &["malloc", "calloc"],
+    release: "free",
+    description: "Memory",
+    acquired: "allocated",
+};
+
+/// # Rule W:F.
+///
+/// See module-level documentation for details.
+pub struct RuleWf {
+    config: RuleWfConfig,
+}
+
+impl RuleWf {
+    /// Constructs a new instance of this rule.
+    #[must_use]
+    pub fn new(config: RuleWfConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the kind of resource acquired by the function with the given name, if it is one
+    /// this rule checks.
+    fn resource(&self, function: &str) -> Option<&'static Resource> {
+        if FILE.acquire.contains(&function) {
+            Some(&FILE)
+        } else if self.config.check_memory && MEMORY.acquire.contains(&function) {
+            Some(&MEMORY)
+        } else {
+            None
+        }
+    }
+}
+
+impl Rule for RuleWf {
+    fn code(&self) -> RuleCode {
+        RuleCode::westwood('F')
+    }
+
+    fn title(&self) -> &'static str {
+        "Close every opened file and free all allocated memory"
+    }
+
+    fn check(&self, SourceInfo { tree, code, .. }: &SourceInfo) -> Vec<Diagnostic<()>> {
+        let helper = QueryHelper::new(QUERY_STR, tree, code);
+        let mut diagnostics = Vec::new();
+        helper.for_each_capture(|label: &str, capture: QueryCapture| match label {
+            "call" => {
+                let call = capture.node;
+                let function = &code[call.child_by_field_name("function").unwrap().byte_range()];
+                let Some(resource) = self.resource(function) else {
+                    return;
+                };
+                let Some((variable, body)) = local_variable(call, code) else {
+                    return;
+                };
+                let mut walker = Walker {
+                    code,
+                    variable,
+                    acquisition: call,
+                    release: resource.release,
+                    frames: Vec::new(),
+                    leaks: Vec::new(),
+                };
+                if walker.exec(body, false) == Some(true) {
+                    // Falls off the end of the function
+                    let brace = body.child(body.child_count() - 1).unwrap();
+                    walker.leaks.push((brace.byte_range(), "end"));
+                }
+                if walker.leaks.is_empty() {
+                    return;
+                }
+                let release = resource.release;
+                let mut diagnostic = Diagnostic::warning()
+                    .with_message(format!(
+                        "{} {} by `{function}()' may be leaked",
+                        resource.description, resource.acquired
+                    ))
+                    .with_label(
+                        Label::primary((), call.byte_range())
+                            .with_message(format!("`{variable}' is {} here", resource.acquired)),
+                    );
+                for (range, verb) in walker.leaks {
+                    diagnostic =
+                        diagnostic.with_label(Label::secondary((), range).with_message(format!(
+                        "Function can {verb} here without passing `{variable}' to `{release}()'"
+                    )));
+                }
+                diagnostics.push(diagnostic);
+            }
+            _ => unreachable!(),
+        });
+        diagnostics
+    }
+}
+
+/// If the value of the given `call_expression` node is stored in a local variable of the
+/// enclosing function, returns the name of the variable and the body of the function.
+fn local_variable<'tree, 'code>(
+    call: Node<'tree>,
+    code: &'code str,
+) -> Option<(&'code str, Node<'tree>)> {
+    // Find the expression which contains the call, like `(FILE *) fopen(...)`, before stripping
+    let mut value = call;
+    let mut parent = call.parent()?;
+    while matches!(parent.kind(), "parenthesized_expression" | "cast_expression") {
+        value = parent;
+        parent = parent.parent()?;
+    }
+    let variable = match parent.kind() {
+        "init_declarator" if parent.child_by_field_name("value") == Some(value) => {
+            declarator_name(parent.child_by_field_name("declarator")?)?
+        }
+        "assignment_expression" if parent.child_by_field_name("right") == Some(value) => {
+            let left = parent.child_by_field_name("left")?;
+            (left.kind() == "identifier").then_some(left)?
+        }
+        _ => return None,
+    };
+    let name = &code[variable.byte_range()];
+    let mut function = parent;
+    while function.kind() != "function_definition" {
+        function = function.parent()?;
+    }
+    let body = function.child_by_field_name("body")?;
+    declares(body, name, code).then_some((name, body))
+}
+
+/// Returns the identifier node declared by a declarator, such as `*fp` or `fp = NULL`.
+fn declarator_name(mut declarator: Node) -> Option<Node> {
+    while declarator.kind() != "identifier" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    Some(declarator)
+}
+
+/// Returns `true` if any declaration within the given node declares a variable with the given
+/// name.
+fn declares(node: Node, name: &str, code: &str) -> bool {
+    let mut cursor = node.walk();
+    if node.kind() == "declaration" {
+        return node
+            .children_by_field_name("declarator", &mut cursor)
+            .filter_map(declarator_name)
+            .any(|identifier| &code[identifier.byte_range()] == name);
+    }
+    let found = node.named_children(&mut cursor).any(|child| declares(child, name, code));
+    found
+}
+
+/// Strips parentheses and casts from an expression.
+fn strip(mut node: Node) -> Node {
+    while let Some(inner) = match node.kind() {
+        "parenthesized_expression" => node.named_child(0),
+        "cast_expression" => node.child_by_field_name("value"),
+        _ => None,
+    } {
+        node = inner;
+    }
+    node
+}
+
+/// Joins the states of two paths which meet. [`None`] means a path can't be taken, and
+/// `Some(true)` means the resource may still be held.
+fn join(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(false) || b.unwrap_or(false)),
+    }
+}
+
+/// Break target of a path through a loop or `switch` statement.
+struct Frame {
+    is_loop: bool,
+    /// Joined state of all paths which leave the statement through `break` (or `continue`).
+    exit: Option<bool>,
+}
+
+/// Walks the paths through a function, tracking whether one resource may still be held.
+struct Walker<'a> {
+    code: &'a str,
+    /// Name of the variable holding the resource.
+    variable: &'a str,
+    /// Call which acquires the resource.
+    acquisition: Node<'a>,
+    /// Name of the function which releases the resource.
+    release: &'a str,
+    /// Enclosing loop and `switch` statements.
+    frames: Vec<Frame>,
+    /// Range and verb of each point at which the function ends while the resource may be held.
+    leaks: Vec<(Range<usize>, &'static str)>,
+}
+
+impl Walker<'_> {
+    /// Follows all paths through a statement, given whether the resource may be held before it.
+    /// Returns whether the resource may be held after the statement, or [`None`] if the end of
+    /// the statement can't be reached.
+    fn exec(&mut self, node: Node, held: bool) -> Option<bool> {
+        let field = |name: &str| node.child_by_field_name(name);
+        match node.kind() {
+            "compound_statement" => {
+                let mut held = held;
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    held = self.exec(child, held)?;
+                }
+                Some(held)
+            }
+            "expression_statement" if self.is_exit_call(node) => None,
+            "return_statement" => {
+                let held = self.effects(node, held);
+                let returned = node.named_child(0).is_some_and(|value| self.mentions(value));
+                if held && !returned {
+                    self.leaks.push((node.byte_range(), "return"));
+                }
+                None
+            }
+            "if_statement" => {
+                let condition = field("condition")?;
+                let held = self.effects(condition, held);
+                let then =
+                    self.exec(field("consequence")?, held && !self.null_when(condition, true));
+                let held = held && !self.null_when(condition, false);
+                let otherwise = match field("alternative") {
+                    Some(alternative) => self.exec(alternative, held),
+                    None => Some(held),
+                };
+                join(then, otherwise)
+            }
+            "else_clause" => self.exec(node.named_child(0)?, held),
+            "while_statement" | "for_statement" => {
+                let mut held = held;
+                if let Some(initializer) = field("initializer") {
+                    held = self.effects(initializer, held);
+                }
+                let condition = field("condition");
+                if let Some(condition) = condition {
+                    held = self.effects(condition, held);
+                }
+                let null_when = |truth| condition.is_some_and(|c| self.null_when(c, truth));
+                let (in_body, on_exit) = (held && !null_when(true), held && !null_when(false));
+                self.frames.push(Frame {
+                    is_loop: true,
+                    exit: None,
+                });
+                let body = self.exec(field("body")?, in_body);
+                let frame = self.frames.pop().unwrap();
+                let exit = if condition.is_none_or(|c| self.is_infinite(c)) {
+                    None
+                } else {
+                    Some(on_exit)
+                };
+                join(join(exit, body), frame.exit)
+            }
+            "do_statement" => {
+                self.frames.push(Frame {
+                    is_loop: true,
+                    exit: None,
+                });
+                let body = self.exec(field("body")?, held);
+                let frame = self.frames.pop().unwrap();
+                let condition = field("condition")?;
+                let body = body.map(|held| self.effects(condition, held));
+                join(body, frame.exit)
+            }
+            "switch_statement" => {
+                let held = self.effects(field("condition")?, held);
+                self.frames.push(Frame {
+                    is_loop: false,
+                    exit: None,
+                });
+                let mut state = None;
+                let mut has_default = false;
+                let body = field("body")?;
+                let mut cursor = body.walk();
+                for child in body.named_children(&mut cursor) {
+                    if child.kind() != "case_statement" {
+                        state = state.and_then(|state| self.exec(child, state));
+                        continue;
+                    }
+                    let value = child.child_by_field_name("value");
+                    has_default |= value.is_none();
+                    state = join(state, Some(held));
+                    let mut case_cursor = child.walk();
+                    for statement in child.named_children(&mut case_cursor) {
+                        if Some(statement) != value {
+                            state = state.and_then(|state| self.exec(statement, state));
+                        }
+                    }
+                }
+                let frame = self.frames.pop().unwrap();
+                let exit = join(state, frame.exit);
+                if has_default {
+                    exit
+                } else {
+                    join(exit, Some(held))
+                }
+            }
+            "break_statement" => {
+                if let Some(frame) = self.frames.last_mut() {
+                    frame.exit = join(frame.exit, Some(held));
+                }
+                None
+            }
+            "continue_statement" => {
+                // Continuing can exit the loop when the condition is checked again
+                if let Some(frame) = self.frames.iter_mut().rev().find(|frame| frame.is_loop) {
+                    frame.exit = join(frame.exit, Some(held));
+                }
+                None
+            }
+            "goto_statement" => None,
+            "labeled_statement" => self.exec(node.named_child(node.named_child_count() - 1)?, held),
+            _ => Some(self.effects(node, held)),
+        }
+    }
+
+    /// Applies the effects of an expression or declaration, in order, to the state of the
+    /// resource.
+    fn effects(&self, node: Node, mut held: bool) -> bool {
+        if node == self.acquisition {
+            return true;
+        }
+        match node.kind() {
+            "call_expression" => {
+                let function =
+                    node.child_by_field_name("function").map(|f| &self.code[f.byte_range()]);
+                let first_argument = node
+                    .child_by_field_name("arguments")
+                    .and_then(|arguments| arguments.named_child(0));
+                if function == Some(self.release)
+                    && first_argument.is_some_and(|arg| self.is_variable(arg))
+                {
+                    return false;
+                }
+            }
+            "assignment_expression" => {
+                let left = node.child_by_field_name("left");
+                let right = node.child_by_field_name("right");
+                if right.is_some_and(|right| self.is_variable(right)) {
+                    // Stored somewhere else
+                    held = false;
+                } else if left.is_some_and(|left| self.is_variable(left))
+                    && right.map(strip) != Some(self.acquisition)
+                {
+                    // Overwritten, so we stop tracking it
+                    return false;
+                }
+            }
+            "init_declarator"
+                if node
+                    .child_by_field_name("value")
+                    .is_some_and(|value| self.is_variable(value)) =>
+            {
+                // Stored in another variable
+                held = false;
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            held = self.effects(child, held);
+        }
+        held
+    }
+
+    /// Returns `true` if the given expression is the tracked variable.
+    fn is_variable(&self, node: Node) -> bool {
+        let node = strip(node);
+        node.kind() == "identifier" && &self.code[node.byte_range()] == self.variable
+    }
+
+    /// Returns `true` if the given expression refers to the tracked variable anywhere.
+    fn mentions(&self, node: Node) -> bool {
+        if self.is_variable(node) {
+            return true;
+        }
+        let mut cursor = node.walk();
+        let found = node.named_children(&mut cursor).any(|child| self.mentions(child));
+        found
+    }
+
+    /// Returns `true` if the variable must be `NULL` when the given condition evaluates to
+    /// `truth`.
+    fn null_when(&self, condition: Node, truth: bool) -> bool {
+        let condition = strip(condition);
+        // Treat `(fp = fopen(...))` like `fp`
+        let is_variable = |node: Node| {
+            let node = strip(node);
+            self.is_variable(node)
+                || (node.kind() == "assignment_expression"
+                    && node.child_by_field_name("left").is_some_and(|left| self.is_variable(left)))
+        };
+        if is_variable(condition) {
+            return !truth;
+        }
+        let operator = condition.child_by_field_name("operator").map(|op| op.kind());
+        match (condition.kind(), operator) {
+            ("unary_expression", Some("!")) => condition
+                .child_by_field_name("argument")
+                .is_some_and(|argument| self.null_when(argument, !truth)),
+            ("binary_expression", Some(operator @ ("==" | "!=" | "&&" | "||"))) => {
+                let (Some(left), Some(right)) =
+                    (condition.child_by_field_name("left"), condition.child_by_field_name("right"))
+                else {
+                    return false;
+                };
+                match operator {
+                    "==" | "!=" => {
+                        let compares_null = (is_variable(left) && self.is_null(right))
+                            || (self.is_null(left) && is_variable(right));
+                        compares_null && ((operator == "==") == truth)
+                    }
+                    // A true `&&` or false `||` means both operands have that value
+                    "&&" if truth => self.null_when(left, true) || self.null_when(right, true),
+                    "||" if !truth => self.null_when(left, false) || self.null_when(right, false),
+                    // Otherwise, either operand may have had that value
+                    _ => self.null_when(left, truth) && self.null_when(right, truth),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the given expression is a null pointer constant.
+    fn is_null(&self, node: Node) -> bool {
+        let node = strip(node);
+        node.kind() == "null" || matches!(&self.code[node.byte_range()], "NULL" | "0")
+    }
+
+    /// Returns `true` if the given loop condition is a non-zero constant.
+    fn is_infinite(&self, condition: Node) -> bool {
+        let condition = strip(condition);
+        match condition.kind() {
+            "number_literal" => &self.code[condition.byte_range()] != "0",
+            "true" => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the given `expression_statement` node calls a function which ends the
+    /// program.
+    fn is_exit_call(&self, statement: Node) -> bool {
+        statement
+            .named_child(0)
+            .filter(|call| call.kind() == "call_expression")
+            .and_then(|call| call.child_by_field_name("function"))
+            .is_some_and(|function| EXIT_FUNCTIONS.contains(&&self.code[function.byte_range()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{Rule, SourceInfo};
+
+    use super::{RuleWf, RuleWfConfig};
+
+    const CODE: &str = indoc! {r#"
+        int main() {
+          FILE *fp = fopen("a.txt", "r");
+          if (fp == NULL) {
+            return 1;
+          }
+          if (bad) {
+            return 2;
+          }
+          fclose(fp);
+          return 0;
+        }
+
+        FILE *open_log(void) {
+          FILE *log = fopen("log.txt", "w");
+          if (!log) return NULL;
+          return log;
+        }
+
+        void store(struct s *s) {
+          FILE *fp;
+          if ((fp = fopen("x.txt", "r")) == NULL) {
+            exit(1);
+          }
+          s->fp = fp;
+        }
+
+        void loop(void) {
+          for (int i = 0; i < 3; i++) {
+            FILE *fp = fopen("x.txt", "r");
+            if (fp == NULL) {
+              continue;
+            }
+            while (1) {
+              if (done) break;
+            }
+            switch (i) {
+              case 0:
+                fclose(fp);
+                break;
+              default:
+                fclose(fp);
+            }
+          }
+        }
+
+        void falls_off(void) {
+          FILE *fp = fopen("x.txt", "r");
+          if (fp != NULL) {
+            fprintf(fp, "hi");
+          }
+        }
+
+        void memory(void) {
+          char *buf = malloc(10);
+          free(buf);
+          char *leak = calloc(1, 1);
+        }
+
+        void casts(void) {
+          FILE *fp = (FILE *) fopen("x.txt", "r");
+          char *buf;
+          buf = (char *) (malloc(10));
+        }
+    "#};
+
+    /// Returns the range of the first occurrence of `text` in the function whose definition
+    /// starts with `function`.
+    fn find(function: &str, text: &str) -> Range<usize> {
+        let function = CODE.find(function).unwrap();
+        let start = function + CODE[function..].find(text).unwrap();
+        start..(start + text.len())
+    }
+
+    #[test]
+    fn files() {
+        let source = SourceInfo::new(CODE);
+        let expected = vec![
+            Diagnostic::warning()
+                .with_message("File opened by `fopen()' may be leaked")
+                .with_label(
+                    Label::primary((), find("int main", r#"fopen("a.txt", "r")"#))
+                        .with_message("`fp' is opened here"),
+                )
+                .with_label(
+                    Label::secondary((), find("int main", "return 2;")).with_message(
+                        "Function can return here without passing `fp' to `fclose()'",
+                    ),
+                ),
+            Diagnostic::warning()
+                .with_message("File opened by `fopen()' may be leaked")
+                .with_label(
+                    Label::primary((), find("void falls_off", r#"fopen("x.txt", "r")"#))
+                        .with_message("`fp' is opened here"),
+                )
+                .with_label(
+                    Label::secondary(
+                        (),
+                        find("void falls_off", "\n}").end - 1..find("void falls_off", "\n}").end,
+                    )
+                    .with_message("Function can end here without passing `fp' to `fclose()'"),
+                ),
+            Diagnostic::warning()
+                .with_message("File opened by `fopen()' may be leaked")
+                .with_label(
+                    Label::primary((), find("void casts", r#"fopen("x.txt", "r")"#))
+                        .with_message("`fp' is opened here"),
+                )
+                .with_label(
+                    Label::secondary(
+                        (),
+                        find("void casts", "\n}").end - 1..find("void casts", "\n}").end,
+                    )
+                    .with_message("Function can end here without passing `fp' to `fclose()'"),
+                ),
+        ];
+        assert_eq!(expected, RuleWf::new(RuleWfConfig::default()).check(&source));
+    }
+
+    #[test]
+    fn memory() {
+        let source = SourceInfo::new(CODE);
+        let rule = RuleWf::new(RuleWfConfig { check_memory: true });
+        let diagnostics = rule.check(&source);
+        assert_eq!(5, diagnostics.len());
+        assert_eq!(
+            Diagnostic::warning()
+                .with_message("Memory allocated by `calloc()' may be leaked")
+                .with_label(
+                    Label::primary((), find("void memory", "calloc(1, 1)"))
+                        .with_message("`leak' is allocated here")
+                )
+                .with_label(
+                    Label::secondary(
+                        (),
+                        find("void memory", "\n}").end - 1..find("void memory", "\n}").end
+                    )
+                    .with_message("Function can end here without passing `leak' to `free()'"),
+                ),
+            diagnostics[2]
+        );
+        assert_eq!(
+            Diagnostic::warning()
+                .with_message("Memory allocated by `malloc()' may be leaked")
+                .with_label(
+                    Label::primary((), find("void casts", "malloc(10)"))
+                        .with_message("`buf' is allocated here")
+                )
+                .with_label(
+                    Label::secondary(
+                        (),
+                        find("void casts", "\n}").end - 1..find("void casts", "\n}").end
+                    )
+                    .with_message("Function can end here without passing `buf' to `free()'"),
+                ),
+            diagnostics[4]
+        );
+    }
+}
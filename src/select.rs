@@ -0,0 +1,117 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Selection of [rules][crate::rules::api::Rule] by code, as done by the `--select` and
+//! `--ignore` command-line options.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::rules::api::{parse_section, section_name, RuleCode};
+
+/// Selects one rule or a whole section of rules.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RuleSelector {
+    /// Selects the rule with the given code, e.g. `III:B`.
+    Rule(RuleCode),
+    /// Selects all rules in the given section, e.g. `III` or `III:*`.
+    Section(u8),
+}
+
+impl RuleSelector {
+    /// Returns `true` if this selector selects the rule with the given code.
+    #[must_use]
+    pub fn matches(self, code: RuleCode) -> bool {
+        match self {
+            Self::Rule(selected) => selected == code,
+            Self::Section(section) => section == code.section,
+        }
+    }
+}
+
+impl FromStr for RuleSelector {
+    type Err = String;
+
+    /// Parses a selector, which is either a rule code (e.g. `III:B`) or a section number, which
+    /// may be followed by `:*` (e.g. `III` or `III:*`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let section = s.strip_suffix(":*").unwrap_or(s);
+        if let Some(section) = parse_section(section) {
+            return Ok(Self::Section(section));
+        }
+        s.parse().map(Self::Rule).map_err(|_| {
+            format!(
+                "Invalid rule selector `{s}': expected a code like `III:B' or a section like `III'"
+            )
+        })
+    }
+}
+
+impl Display for RuleSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rule(code) => code.fmt(f),
+            Self::Section(section) => f.write_str(&section_name(*section)),
+        }
+    }
+}
+
+/// Returns `true` if the rule with the given code should be checked.
+///
+/// If `select` is non-empty, only rules matched by one of its selectors are checked. Rules
+/// matched by a selector in `ignore` are never checked.
+#[must_use]
+pub fn is_selected(code: RuleCode, select: &[RuleSelector], ignore: &[RuleSelector]) -> bool {
+    (select.is_empty() || select.iter().any(|selector| selector.matches(code)))
+        && !ignore.iter().any(|selector| selector.matches(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{RuleCode, WESTWOOD_SECTION};
+
+    use super::RuleSelector;
+
+    #[test]
+    fn parse() {
+        let tests = [
+            ("III", RuleSelector::Section(3)),
+            ("iii:*", RuleSelector::Section(3)),
+            ("III:B", RuleSelector::Rule(RuleCode::new(3, 'B'))),
+            ("XII:A", RuleSelector::Rule(RuleCode::new(12, 'A'))),
+            ("W", RuleSelector::Section(WESTWOOD_SECTION)),
+        ];
+        for (text, selector) in tests {
+            assert_eq!(Ok(selector), text.parse());
+        }
+        for text in ["", "3", "III:", "IIV", "III:B:C"] {
+            assert!(text.parse::<RuleSelector>().is_err(), "{text}");
+        }
+        assert_eq!("XI", RuleSelector::Section(11).to_string());
+        assert_eq!("W", RuleSelector::Section(WESTWOOD_SECTION).to_string());
+    }
+
+    #[test]
+    fn is_selected() {
+        let code = RuleCode::new(3, 'B');
+        let section = RuleSelector::Section(3);
+        let other = RuleSelector::Rule(RuleCode::new(2, 'B'));
+        assert!(super::is_selected(code, &[], &[]));
+        assert!(super::is_selected(code, &[section], &[]));
+        assert!(!super::is_selected(code, &[other], &[]));
+        assert!(super::is_selected(code, &[], &[other]));
+        assert!(!super::is_selected(code, &[section], &[RuleSelector::Rule(code)]));
+    }
+}
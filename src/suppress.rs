@@ -0,0 +1,372 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Suppression of diagnostics using comments in the source code.
+//!
+//! # Syntax
+//!
+//! A suppression comment is a comment whose text begins with one of the following directives,
+//! optionally followed by a list of rule codes separated by spaces and/or commas. If no codes are
+//! given, all diagnostics are suppressed. The list ends at the first word which is not a rule
+//! code, so the rest of the comment can give a reason for the suppression.
+//!
+//! - `westwood-ignore`: Suppresses diagnostics on the line following the comment.
+//! - `westwood-ignore-line`: Suppresses diagnostics on the line on which the comment starts.
+//! - `westwood-ignore-file`: Suppresses diagnostics in the entire file.
+//!
+//! A diagnostic is considered to be on the line on which its first primary label starts.
+//!
+//! ```c
+//! /* westwood-ignore II:A */
+//! // See https://example.com/a/very/long/url/which/would/otherwise/exceed/the/line/length/limit
+//!
+//! goto end; // westwood-ignore-line XI:E -- cleanup is shared by all error paths
+//! ```
+//!
+//! # Unused suppressions
+//!
+//! To prevent suppression comments from going stale, a warning with the code
+//! [`W:A`][UNUSED_SUPPRESSION] is produced for each rule code in a suppression comment which does
+//! not suppress any diagnostics, or for the whole comment if it contains no codes and does not
+//! suppress anything. Codes which don't belong to any rule, like `III:Z`, are always reported.
+//! Codes of rules which were not checked, for example because they were disabled in the
+//! configuration or by `--ignore`, are not reported.
+//!
+//! These warnings can be turned off using the `report-unused-suppressions` configuration option,
+//! deselected like any rule (e.g. `--ignore W:A`), or suppressed by a comment which names `W:A`
+//! explicitly:
+//!
+//! ```c
+//! int a; // westwood-ignore-line I:A, W:A
+//! ```
+
+use std::ops::Range;
+
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle};
+
+use crate::{
+    helpers::QueryHelper,
+    rules::{
+        self,
+        api::{RuleCode, SourceInfo},
+    },
+};
+
+/// Code of the warnings about unused suppressions.
+pub const UNUSED_SUPPRESSION: RuleCode = RuleCode::westwood('A');
+
+/// Where a suppression comment applies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Scope {
+    /// Applies to the line with the given (0-based) index.
+    Line(usize),
+    /// Applies to the whole file.
+    File,
+}
+
+/// A single suppression comment.
+#[derive(Clone, Debug)]
+struct Directive {
+    scope: Scope,
+    /// Byte range of the comment.
+    range: Range<usize>,
+    /// Rule codes to suppress, along with whether each one has suppressed a diagnostic. If empty,
+    /// all diagnostics are suppressed.
+    codes: Vec<(RuleCode, bool)>,
+    /// Whether this directive has suppressed a diagnostic. Only used if `codes` is empty.
+    used: bool,
+}
+
+/// Set of suppression comments found in a source file.
+#[derive(Clone, Debug)]
+pub struct Suppressions<'src> {
+    directives: Vec<Directive>,
+    /// Byte position of the start of each line.
+    line_starts: Vec<usize>,
+    code: &'src str,
+}
+
+impl<'src> Suppressions<'src> {
+    /// Finds all suppression comments in the given source file.
+    #[must_use]
+    pub fn new(source: &SourceInfo<'src>) -> Self {
+        let mut directives = Vec::new();
+        let helper = QueryHelper::new("(comment) @comment", &source.tree, source.code);
+        helper.for_each_capture(|_label, capture| {
+            let node = capture.node;
+            if let Some((directive, codes)) = parse_comment(&source.code[node.byte_range()]) {
+                let scope = match directive {
+                    "westwood-ignore" => Scope::Line(node.end_position().row + 1),
+                    "westwood-ignore-line" => Scope::Line(node.start_position().row),
+                    "westwood-ignore-file" => Scope::File,
+                    _ => unreachable!(),
+                };
+                directives.push(Directive {
+                    scope,
+                    range: node.byte_range(),
+                    codes: codes.into_iter().map(|code| (code, false)).collect(),
+                    used: false,
+                });
+            }
+        });
+        Self {
+            directives,
+            line_starts: source.lines.iter().map(|&(_line, start)| start).collect(),
+            code: source.code,
+        }
+    }
+
+    /// Returns `true` if the given diagnostic is suppressed by a comment, and marks the
+    /// comment(s) which suppress it as used.
+    pub fn is_suppressed(&mut self, diagnostic: &Diagnostic<()>) -> bool {
+        let Some(label) = diagnostic.labels.iter().find(|label| label.style == LabelStyle::Primary)
+        else {
+            return false;
+        };
+        let row = self.row(label.range.start);
+        let diagnostic_code: Option<RuleCode> =
+            diagnostic.code.as_deref().and_then(|code| code.parse().ok());
+        let mut suppressed = false;
+        for directive in &mut self.directives {
+            if !directive.applies_to(row) {
+                continue;
+            }
+            if directive.codes.is_empty() {
+                directive.used = true;
+                suppressed = true;
+            }
+            for (code, used) in &mut directive.codes {
+                if diagnostic_code == Some(*code) {
+                    *used = true;
+                    suppressed = true;
+                }
+            }
+        }
+        suppressed
+    }
+
+    /// Returns a warning for each suppression comment (or rule code within a comment) which has
+    /// not suppressed any diagnostics, and for each code which doesn't belong to any rule. Unused
+    /// codes of rules which are not in `checked` are not reported, since those rules could not
+    /// have produced diagnostics to suppress.
+    ///
+    /// Warnings are omitted if they are suppressed by a comment naming [`UNUSED_SUPPRESSION`].
+    #[must_use]
+    pub fn unused(&self, checked: &[RuleCode]) -> Vec<Diagnostic<()>> {
+        let mut known: Option<Vec<RuleCode>> = None;
+        let mut diagnostics = Vec::new();
+        for directive in &self.directives {
+            // Don't include the trailing newline of a `//` comment in the label
+            let range = directive.range.start
+                ..(directive.range.start + self.code[directive.range.clone()].trim_end().len());
+            let row = self.row(range.start);
+            if self.directives.iter().any(|other| {
+                other.applies_to(row)
+                    && other.codes.iter().any(|(code, _used)| *code == UNUSED_SUPPRESSION)
+            }) {
+                continue;
+            }
+            let warning = |message: String| {
+                Diagnostic::warning()
+                    .with_code(UNUSED_SUPPRESSION.to_string())
+                    .with_message(message)
+                    .with_label(Label::primary((), range.clone()))
+            };
+
+            if directive.codes.is_empty() {
+                if !directive.used {
+                    diagnostics.push(warning(
+                        "Suppression comment does not suppress any diagnostics".to_owned(),
+                    ));
+                }
+                continue;
+            }
+            let known = known.get_or_insert_with(|| {
+                let mut known = rules::all_codes();
+                known.push(UNUSED_SUPPRESSION);
+                known
+            });
+            let unknown = directive
+                .codes
+                .iter()
+                .filter(|(code, _used)| !known.contains(code))
+                .map(|(code, _used)| code);
+            for code in unknown {
+                diagnostics.push(warning(format!("Suppression of unknown rule `{code}'")));
+            }
+            let unused = directive.codes.iter().filter(|(code, used)| {
+                !used && *code != UNUSED_SUPPRESSION && checked.contains(code)
+            });
+            for (code, _used) in unused {
+                diagnostics.push(warning(format!(
+                    "Suppression of `{code}' does not suppress any diagnostics"
+                )));
+            }
+        }
+        diagnostics
+    }
+
+    /// Returns the (0-based) index of the line containing the given byte offset.
+    fn row(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+}
+
+impl Directive {
+    /// Returns `true` if this directive applies to the line with the given (0-based) index.
+    fn applies_to(&self, row: usize) -> bool {
+        self.scope == Scope::File || self.scope == Scope::Line(row)
+    }
+}
+
+/// Parses the text of a comment. If it is a suppression comment, returns the directive and the
+/// list of rule codes it contains. Words following the list of codes are ignored.
+fn parse_comment(comment: &str) -> Option<(&str, Vec<RuleCode>)> {
+    let text = if let Some(text) = comment.strip_prefix("//") {
+        text
+    } else {
+        let text = comment.strip_prefix("/*")?;
+        text.strip_suffix("*/").unwrap_or(text)
+    };
+    let mut words = text.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty());
+    let directive = words.next()?;
+    match directive {
+        "westwood-ignore" | "westwood-ignore-line" | "westwood-ignore-file" => {
+            Some((directive, words.map_while(|word| word.parse().ok()).collect()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::{RuleCode, SourceInfo};
+
+    use super::Suppressions;
+
+    /// Codes of the rules used in the tests.
+    const CHECKED: [RuleCode; 4] = [
+        RuleCode::new(1, 'A'),
+        RuleCode::new(3, 'B'),
+        RuleCode::new(3, 'C'),
+        RuleCode::new(11, 'E'),
+    ];
+
+    #[test]
+    fn parse_comment() {
+        const I_A: RuleCode = RuleCode::new(1, 'A');
+        const II_A: RuleCode = RuleCode::new(2, 'A');
+        const III_B: RuleCode = RuleCode::new(3, 'B');
+        const XI_E: RuleCode = RuleCode::new(11, 'E');
+        let tests = [
+            ("// westwood-ignore", Some(("westwood-ignore", vec![]))),
+            ("/* westwood-ignore III:B */", Some(("westwood-ignore", vec![III_B]))),
+            (
+                "//westwood-ignore-line I:A, XI:E",
+                Some(("westwood-ignore-line", vec![I_A, XI_E])),
+            ),
+            ("/*westwood-ignore-file II:A*/", Some(("westwood-ignore-file", vec![II_A]))),
+            (
+                "/* westwood-ignore III:B because starter code */",
+                Some(("westwood-ignore", vec![III_B])),
+            ),
+            (
+                "// westwood-ignore I:A, XI:E -- names come from the assignment",
+                Some(("westwood-ignore", vec![I_A, XI_E])),
+            ),
+            ("// westwood-ignore because of XI:E", Some(("westwood-ignore", vec![]))),
+            ("// not westwood-ignore", None),
+            ("// westwood-ignored", None),
+            ("/* */", None),
+        ];
+        for (comment, expected) in tests {
+            assert_eq!(expected, super::parse_comment(comment), "{comment}");
+        }
+    }
+
+    /// Returns a diagnostic with the given code whose primary label starts at the beginning of the
+    /// given (0-based) line.
+    fn diagnostic_at_line(code: &str, line: usize, source: &SourceInfo) -> Diagnostic<()> {
+        let start = source.lines[line].1;
+        Diagnostic::warning()
+            .with_code(code)
+            .with_label(Label::primary((), start..(start + 1)))
+    }
+
+    #[test]
+    fn suppressions() {
+        let code = indoc! { /* c */ r"
+            /* westwood-ignore III:B */
+            int a;
+            int b; // westwood-ignore-line
+            // westwood-ignore XI:E, I:A
+            int c;
+        " };
+        let source = SourceInfo::new(code);
+        let mut suppressions = Suppressions::new(&source);
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("III:B", 1, &source)));
+        assert!(!suppressions.is_suppressed(&diagnostic_at_line("III:C", 1, &source)));
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("III:B", 2, &source)));
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("I:A", 2, &source)));
+        assert!(!suppressions.is_suppressed(&diagnostic_at_line("I:A", 3, &source)));
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("XI:E", 4, &source)));
+        assert!(!suppressions.is_suppressed(&diagnostic_at_line("XI:E", 3, &source)));
+
+        // The I:A code in the last comment was never used
+        let unused = suppressions.unused(&CHECKED);
+        assert_eq!(1, unused.len());
+        assert_eq!("Suppression of `I:A' does not suppress any diagnostics", unused[0].message);
+        assert_eq!("// westwood-ignore XI:E, I:A", &code[unused[0].labels[0].range.clone()]);
+
+        assert_eq!(Some("W:A"), unused[0].code.as_deref());
+
+        // Unless I:A wasn't checked
+        assert!(suppressions.unused(&CHECKED[1..]).is_empty());
+    }
+
+    /// Tests that codes are matched regardless of case, that unknown codes are reported even
+    /// though they can never be used, and that words following the codes are not.
+    #[test]
+    fn code_matching() {
+        let code = indoc! { /* c */ r"
+            int a; // westwood-ignore-line iii:b
+            int b; // westwood-ignore-line III:Z because starter code
+            int c; // westwood-ignore-line III:Z, I:A, W:A
+        " };
+        let source = SourceInfo::new(code);
+        let mut suppressions = Suppressions::new(&source);
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("III:B", 0, &source)));
+        // Unknown codes don't make the comment suppress everything
+        assert!(!suppressions.is_suppressed(&diagnostic_at_line("III:B", 1, &source)));
+
+        let unused: Vec<String> =
+            suppressions.unused(&CHECKED).into_iter().map(|d| d.message).collect();
+        assert_eq!(vec!["Suppression of unknown rule `III:Z'"], unused);
+    }
+
+    #[test]
+    fn file_suppression() {
+        let code = "// westwood-ignore-file\nint a;\nint b;\n";
+        let source = SourceInfo::new(code);
+        let mut suppressions = Suppressions::new(&source);
+        assert!(suppressions.unused(&CHECKED).len() == 1);
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("I:A", 1, &source)));
+        assert!(suppressions.is_suppressed(&diagnostic_at_line("III:B", 2, &source)));
+        assert!(suppressions.unused(&CHECKED).is_empty());
+    }
+}
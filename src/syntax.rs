@@ -0,0 +1,289 @@
+// Copyright (C) 2025 Kian Kasad <kian@kasad.com>
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reporting of syntax errors.
+//!
+//! When Tree-sitter encounters a syntax error, it recovers by either inserting a zero-width
+//! `MISSING` node for a token it expected to find, or by wrapping the text it could not parse in
+//! an `ERROR` node. Each of these becomes an error diagnostic.
+//!
+//! For a `MISSING` node, the diagnostic names the expected token. For an `ERROR` node, it quotes
+//! the unexpected text and lists the tokens which the parser would have accepted in its place.
+//! These are found by looking up the parse state in which the parser encountered the first token
+//! of the `ERROR` node. Preprocessor directives, which are accepted almost anywhere, and compiler
+//! extensions such as `__attribute__` are left out of the list. If the unexpected token is itself
+//! one of the accepted ones, the parser only failed on a later token, so no list is given.
+//!
+//! Since the tree around a syntax error may not reflect what the author intended, rules can
+//! produce false positives there. To avoid this, each syntax error has an affected region, and
+//! diagnostics produced by rules whose primary labels fall within an affected region are
+//! discarded. The affected region of a syntax error is the statement or top-level declaration
+//! containing it, extended to cover whole lines.
+
+use std::ops::Range;
+
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle};
+use tree_sitter::Node;
+
+use crate::rules::api::SourceInfo;
+
+/// Maximum number of characters of unexpected text to include in a diagnostic message.
+const MAX_SNIPPET_LENGTH: usize = 20;
+
+/// Maximum number of expected tokens to list for an `ERROR` node.
+const MAX_EXPECTED_TOKENS: usize = 12;
+
+/// Syntax errors found in a source file.
+#[derive(Clone, Debug, Default)]
+pub struct SyntaxErrors {
+    /// Diagnostics describing each syntax error.
+    pub diagnostics: Vec<Diagnostic<()>>,
+    /// Byte ranges of the regions affected by syntax errors.
+    regions: Vec<Range<usize>>,
+}
+
+impl SyntaxErrors {
+    /// Finds all syntax errors in the given source file.
+    #[must_use]
+    pub fn new(source: &SourceInfo) -> Self {
+        let mut errors = Self::default();
+        if source.tree.root_node().has_error() {
+            errors.visit(source.tree.root_node(), source);
+        }
+        errors
+    }
+
+    /// Returns `true` if the given diagnostic's primary label falls within a region affected by a
+    /// syntax error. Labels which contain an entire affected region, e.g. one spanning a whole
+    /// function, are not considered to fall within it.
+    #[must_use]
+    pub fn is_affected(&self, diagnostic: &Diagnostic<()>) -> bool {
+        diagnostic
+            .labels
+            .iter()
+            .filter(|label| label.style == LabelStyle::Primary)
+            .any(|label| {
+                self.regions.iter().any(|region| {
+                    let overlaps = region.contains(&label.range.start)
+                        || (label.range.start < region.end && region.start < label.range.end);
+                    let contains =
+                        label.range.start <= region.start && region.end <= label.range.end;
+                    overlaps && !contains
+                })
+            })
+    }
+
+    /// Searches `node` and its descendants for syntax errors.
+    fn visit(&mut self, node: Node, source: &SourceInfo) {
+        if node.is_missing() {
+            let expected = describe_token(node.kind(), node.is_named());
+            self.push(
+                node,
+                source,
+                Diagnostic::error()
+                    .with_message(format!("Syntax error: expected {expected}"))
+                    .with_label(
+                        Label::primary((), node.byte_range())
+                            .with_message(format!("Expected {expected} here")),
+                    ),
+            );
+        } else if node.is_error() {
+            let text = &source.code[node.byte_range()];
+            let first_line = text.lines().next().unwrap_or_default().trim_end();
+            let mut snippet: String = first_line.chars().take(MAX_SNIPPET_LENGTH).collect();
+            if snippet.len() < text.trim_end().len() {
+                snippet.push_str("...");
+            }
+            let mut diagnostic = Diagnostic::error()
+                .with_message(format!("Syntax error: unexpected `{snippet}'"))
+                .with_label(
+                    Label::primary((), node.byte_range()).with_message("Could not parse this"),
+                );
+            if let Some(expected) = expected_tokens(node) {
+                diagnostic = diagnostic.with_note(format!("Expected {expected}"));
+            }
+            self.push(node, source, diagnostic);
+            // Don't report errors nested within this one
+        } else if node.has_error() {
+            for child in node.children(&mut node.walk()) {
+                self.visit(child, source);
+            }
+        }
+    }
+
+    /// Adds a diagnostic for the syntax error at `node` and records the region it affects.
+    fn push(&mut self, node: Node, source: &SourceInfo, diagnostic: Diagnostic<()>) {
+        self.diagnostics.push(
+            diagnostic
+                .with_note("Other rules are not checked on the lines affected by this error."),
+        );
+
+        // Find the statement or top-level declaration containing the error
+        let mut statement = node;
+        while let Some(parent) = statement.parent() {
+            if matches!(parent.kind(), "translation_unit" | "compound_statement") {
+                break;
+            }
+            statement = parent;
+        }
+
+        // Extend the region to whole lines
+        let lines = &source.lines;
+        let start = lines[statement.start_position().row].1;
+        let end = lines
+            .get(statement.end_position().row + 1)
+            .map_or(source.code.len(), |&(_line, start)| start);
+        self.regions.push(start..end);
+    }
+}
+
+/// Returns a description of the tokens which the parser would have accepted in place of the
+/// `ERROR` node `node`, e.g. "`,' or `)'". Returns [`None`] if they can't be determined.
+fn expected_tokens(node: Node) -> Option<String> {
+    let mut first_leaf = node;
+    while let Some(child) = first_leaf.child(0) {
+        first_leaf = child;
+    }
+    let language = node.language();
+    // Tree-sitter numbers terminal symbols (tokens) before non-terminal ones, and the first
+    // non-terminal is the grammar's start rule.
+    let first_non_terminal = language.id_for_node_kind("translation_unit", true);
+    let mut expected: Vec<(&str, bool)> = Vec::new();
+    for symbol in language.lookahead_iterator(first_leaf.parse_state())? {
+        let Some(kind) = language.node_kind_for_id(symbol) else {
+            continue;
+        };
+        if symbol >= first_non_terminal
+            || !language.node_kind_is_visible(symbol)
+            || is_ignored_token(kind)
+        {
+            continue;
+        }
+        let token = (kind, language.node_kind_is_named(symbol));
+        if !expected.contains(&token) {
+            expected.push(token);
+        }
+    }
+    if expected.is_empty() || expected.contains(&(first_leaf.kind(), first_leaf.is_named())) {
+        return None;
+    }
+
+    let mut descriptions: Vec<String> = expected
+        .iter()
+        .take(MAX_EXPECTED_TOKENS)
+        .map(|&(kind, named)| describe_token(kind, named))
+        .collect();
+    let others = expected.len() - descriptions.len();
+    if others > 0 {
+        descriptions.push(format!("{others} other tokens"));
+    }
+    Some(match descriptions.as_slice() {
+        [only] => only.clone(),
+        [first, second] => format!("{first} or {second}"),
+        [rest @ .., last] => format!("{}, or {last}", rest.join(", ")),
+        [] => unreachable!(),
+    })
+}
+
+/// Returns `true` if the token with the given kind should not be listed as expected: comments,
+/// preprocessor directives, and compiler extensions, which are accepted almost anywhere or are not
+/// standard C.
+fn is_ignored_token(kind: &str) -> bool {
+    matches!(kind, "comment" | "preproc_directive")
+        || kind.starts_with('#')
+        || kind.starts_with('_')
+        || kind.starts_with("ms_")
+}
+
+/// Returns a description of a token with the given kind, e.g. `;' or identifier.
+fn describe_token(kind: &str, named: bool) -> String {
+    if named {
+        kind.to_owned()
+    } else {
+        format!("`{kind}'")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use codespan_reporting::diagnostic::{Diagnostic, Label};
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::rules::api::SourceInfo;
+
+    use super::SyntaxErrors;
+
+    #[test]
+    fn missing() {
+        let code = "int main() {\n  int x = 1\n  return x;\n}\n";
+        let errors = SyntaxErrors::new(&SourceInfo::new(code));
+        assert_eq!(1, errors.diagnostics.len());
+        assert_eq!("Syntax error: expected `;'", errors.diagnostics[0].message);
+        assert_eq!(24..24, errors.diagnostics[0].labels[0].range);
+        assert_eq!(vec![13..25], errors.regions);
+    }
+
+    #[test]
+    fn unexpected() {
+        let code = "int main() {\n  foo(1 2);\n  return 0;\n}\n";
+        let errors = SyntaxErrors::new(&SourceInfo::new(code));
+        assert_eq!(1, errors.diagnostics.len());
+        assert_eq!("Syntax error: unexpected `1'", errors.diagnostics[0].message);
+    }
+
+    /// Tests that the tokens accepted in place of unexpected text are listed, unless the
+    /// unexpected token is itself accepted.
+    #[test]
+    fn expected_tokens() {
+        let code = "int f(int a b) {}\n";
+        let errors = SyntaxErrors::new(&SourceInfo::new(code));
+        assert_eq!(1, errors.diagnostics.len());
+        assert_eq!("Syntax error: unexpected `b'", errors.diagnostics[0].message);
+        assert_eq!(
+            "Expected `[', `,', `)', `(', `;', `[[', `{', `=', or `asm'",
+            errors.diagnostics[0].notes[0]
+        );
+
+        let code = "int main() {\n  if x {\n  }\n}\n";
+        let errors = SyntaxErrors::new(&SourceInfo::new(code));
+        assert_eq!("Syntax error: unexpected `if x'", errors.diagnostics[0].message);
+        assert!(!errors.diagnostics[0].notes[0].starts_with("Expected"));
+    }
+
+    #[test]
+    fn no_errors() {
+        let errors = SyntaxErrors::new(&SourceInfo::new("int main() {\n  return 0;\n}\n"));
+        assert!(errors.diagnostics.is_empty());
+        assert!(errors.regions.is_empty());
+    }
+
+    /// Tests that only diagnostics within the affected lines are discarded.
+    #[test]
+    fn is_affected() {
+        let code = indoc! { /* c */ "
+            int main() {
+              int x = 1
+              int y = 2;
+              return x;
+            }
+        " };
+        let errors = SyntaxErrors::new(&SourceInfo::new(code));
+        let at = |range| Diagnostic::warning().with_label(Label::primary((), range));
+        assert!(errors.is_affected(&at(15..16)));
+        assert!(errors.is_affected(&at(10..20)));
+        assert!(!errors.is_affected(&at(27..28)));
+        assert!(!errors.is_affected(&at(0..code.len())));
+    }
+}